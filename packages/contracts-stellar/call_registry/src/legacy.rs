@@ -0,0 +1,19 @@
+use soroban_sdk::{contracttype, Address, BytesN, String};
+
+/// `Call` layout stored by schema version 1, before cancellation
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CallV1 {
+    pub creator: Address,
+    pub stake_token: Address,
+    pub total_stake_yes: i128,
+    pub total_stake_no: i128,
+    pub start_ts: u64,
+    pub end_ts: u64,
+    pub token_address: Address,
+    pub pair_id: BytesN<32>,
+    pub ipfs_cid: String,
+    pub settled: bool,
+    pub outcome: bool,
+    pub final_price: i128,
+}
@@ -3,6 +3,23 @@ use soroban_sdk::{
     contract, contractimpl, contracttype, token, Address, BytesN, Env, String, Symbol,
 };
 
+pub mod legacy;
+pub mod manager;
+
+use legacy::CallV1;
+use manager::OutcomeManagerClient;
+
+/// Layout version of stored data written by this code
+/// 1: initial release
+/// 2: `Call::cancelled`
+pub const CURRENT_SCHEMA_VERSION: u32 = 2;
+
+const DAY_IN_LEDGERS: u32 = 17_280;
+/// Default remaining TTL below which an entry is extended (7 days)
+const DEFAULT_TTL_THRESHOLD: u32 = 7 * DAY_IN_LEDGERS;
+/// Default TTL an entry is extended to (30 days)
+const DEFAULT_TTL_EXTEND_TO: u32 = 30 * DAY_IN_LEDGERS;
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Call {
@@ -18,6 +35,7 @@ pub struct Call {
     pub settled: bool,
     pub outcome: bool,
     pub final_price: i128,
+    pub cancelled: bool,
 }
 
 #[contracttype]
@@ -28,6 +46,45 @@ pub struct CreateCallMetadata {
     pub ipfs_cid: String,
 }
 
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TtlConfig {
+    /// Remaining TTL, in ledgers, below which an entry is extended
+    pub threshold: u32,
+    /// TTL, in ledgers, an entry is extended to
+    pub extend_to: u32,
+}
+
+/// Bounds on a single stake in an allowlisted stake token
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StakeTokenLimits {
+    /// Smallest amount a call can be created or staked with
+    pub min_stake: i128,
+    /// Largest amount a call can be created or staked with
+    pub max_stake: i128,
+}
+
+/// Bounds on staking into a call; a zero field is not enforced
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct StakeLimits {
+    /// Smallest amount a single stake may back the call with
+    pub min_stake: i128,
+    /// Largest total a user may hold on the call, across both positions
+    pub max_user_stake: i128,
+    /// Largest total the call may hold, across both positions
+    pub max_pool: i128,
+}
+
+/// Privileged roles the admin can delegate; the admin holds every role
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Role {
+    /// May pause and unpause the contract
+    Pauser,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum DataKey {
@@ -36,6 +93,21 @@ pub enum DataKey {
     UserStake(u64, Address, bool),
     Admin,
     IsPaused,
+    OutcomeManager,
+    TtlConfig,
+    SchemaVersion,
+    MigrationCursor,
+    PendingAdmin,
+    Role(Role, Address),
+    /// Creator reward, in basis points of the losing pool, set at creation
+    CreatorFee(u64),
+    MaxCreatorFee,
+    /// Stake limits of a token the admin allows calls to be staked in
+    StakeToken(Address),
+    /// Stake limits applied to every call
+    StakeLimits,
+    /// Stake limits the creator set for one call
+    CallStakeLimits(u64),
 }
 
 #[contract]
@@ -50,6 +122,137 @@ impl CallRegistry {
             .expect("Admin not set")
     }
 
+    /// Require `caller` to authorize and to be the admin or hold `role`
+    fn require_role(env: &Env, role: Role, caller: &Address) {
+        caller.require_auth();
+        if *caller != Self::get_admin(env)
+            && !env
+                .storage()
+                .persistent()
+                .has(&DataKey::Role(role, caller.clone()))
+        {
+            panic!("Caller lacks role");
+        }
+    }
+
+    fn get_ttl_config(env: &Env) -> TtlConfig {
+        env.storage()
+            .instance()
+            .get(&DataKey::TtlConfig)
+            .unwrap_or(TtlConfig {
+                threshold: DEFAULT_TTL_THRESHOLD,
+                extend_to: DEFAULT_TTL_EXTEND_TO,
+            })
+    }
+
+    /// Extend a persistent entry, and the contract instance and config it
+    /// depends on, so nothing an active call needs is archived
+    fn extend_persistent(env: &Env, key: &DataKey) {
+        let ttl = Self::get_ttl_config(env);
+        let storage = env.storage().persistent();
+        storage.extend_ttl(key, ttl.threshold, ttl.extend_to);
+
+        env.storage()
+            .instance()
+            .extend_ttl(ttl.threshold, ttl.extend_to);
+        for config_key in [
+            DataKey::Admin,
+            DataKey::IsPaused,
+            DataKey::OutcomeManager,
+            DataKey::MaxCreatorFee,
+            DataKey::StakeLimits,
+        ] {
+            if storage.has(&config_key) {
+                storage.extend_ttl(&config_key, ttl.threshold, ttl.extend_to);
+            }
+        }
+    }
+
+    fn outcome_manager_client(env: &Env) -> OutcomeManagerClient<'_> {
+        let outcome_manager: Address = env
+            .storage()
+            .persistent()
+            .get(&DataKey::OutcomeManager)
+            .expect("Outcome manager not set");
+        OutcomeManagerClient::new(env, &outcome_manager)
+    }
+
+    fn get_max_creator_fee(env: &Env) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::MaxCreatorFee)
+            .unwrap_or(0)
+    }
+
+    /// Panic unless the token is allowlisted and the amount is within its
+    /// stake limits
+    fn check_stake_token(env: &Env, stake_token: &Address, amount: i128) {
+        let key = DataKey::StakeToken(stake_token.clone());
+        let limits: StakeTokenLimits = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .expect("Stake token not allowed");
+        Self::extend_persistent(env, &key);
+
+        if amount < limits.min_stake {
+            panic!("Stake below token minimum");
+        }
+        if amount > limits.max_stake {
+            panic!("Stake above token maximum");
+        }
+    }
+
+    fn get_stake_limits(env: &Env) -> StakeLimits {
+        env.storage()
+            .persistent()
+            .get(&DataKey::StakeLimits)
+            .unwrap_or_default()
+    }
+
+    fn validate_stake_limits(limits: &StakeLimits) {
+        if limits.min_stake < 0 || limits.max_user_stake < 0 || limits.max_pool < 0 {
+            panic!("Invalid stake limits");
+        }
+        if limits.max_user_stake > 0 && limits.max_user_stake < limits.min_stake {
+            panic!("Invalid stake limits");
+        }
+        if limits.max_pool > 0 && limits.max_pool < limits.min_stake {
+            panic!("Invalid stake limits");
+        }
+    }
+
+    /// Panic if a stake of `amount` that brings the user's total on the call
+    /// to `user_total` and the call's pool to `pool_total` breaks the global
+    /// or the call's own stake limits
+    fn check_stake_limits(
+        env: &Env,
+        call_id: u64,
+        amount: i128,
+        user_total: i128,
+        pool_total: i128,
+    ) {
+        let call_limits: Option<StakeLimits> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::CallStakeLimits(call_id));
+
+        for limits in [Some(Self::get_stake_limits(env)), call_limits]
+            .into_iter()
+            .flatten()
+        {
+            if amount < limits.min_stake {
+                panic!("Stake below minimum");
+            }
+            if limits.max_user_stake > 0 && user_total > limits.max_user_stake {
+                panic!("Stake exceeds user max");
+            }
+            if limits.max_pool > 0 && pool_total > limits.max_pool {
+                panic!("Stake exceeds pool max");
+            }
+        }
+    }
+
     fn is_paused(env: &Env) -> bool {
         env.storage()
             .persistent()
@@ -63,6 +266,22 @@ impl CallRegistry {
         }
     }
 
+    /// Contracts deployed before versioning have no stored version
+    fn read_schema_version(env: &Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::SchemaVersion)
+            .unwrap_or(1)
+    }
+
+    /// Calls are only read and written once `migrate` has finished, so none
+    /// in the current layout can land among the entries still being migrated
+    fn assert_schema_current(env: &Env) {
+        if Self::read_schema_version(env) < CURRENT_SCHEMA_VERSION {
+            panic!("Migration pending");
+        }
+    }
+
     /// Initialize admin and pause state
     pub fn initialize(env: Env, admin: Address) {
         if env.storage().persistent().has(&DataKey::Admin) {
@@ -72,19 +291,228 @@ impl CallRegistry {
         admin.require_auth();
         env.storage().persistent().set(&DataKey::Admin, &admin);
         env.storage().persistent().set(&DataKey::IsPaused, &false);
+        env.storage()
+            .instance()
+            .set(&DataKey::SchemaVersion, &CURRENT_SCHEMA_VERSION);
     }
 
-    /// Pause write operations (admin only)
-    pub fn pause(env: Env) {
+    /// Replace the contract code, keeping its storage and balances (admin only)
+    /// Run `migrate` afterwards if the new code raises the schema version
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
         let admin = Self::get_admin(&env);
         admin.require_auth();
-        env.storage().persistent().set(&DataKey::IsPaused, &true);
+
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+
+        env.events()
+            .publish((Symbol::new(&env, "ContractUpgraded"),), new_wasm_hash);
+    }
+
+    /// Bring stored data up to `CURRENT_SCHEMA_VERSION` (admin only)
+    /// Rewrites at most `limit` calls per invocation so large registries can
+    /// migrate over several transactions
+    /// Creating, staking on, cancelling and claiming calls are refused until
+    /// the migration completes
+    /// Version 1 stakes are forwarded to the OutcomeManager, so it must be
+    /// migrated first
+    /// Returns the stored schema version after this batch
+    pub fn migrate(env: Env, limit: u32) -> u32 {
+        let admin = Self::get_admin(&env);
+        admin.require_auth();
+
+        if limit == 0 {
+            panic!("Limit must be > 0");
+        }
+
+        let instance = env.storage().instance();
+        let version = Self::read_schema_version(&env);
+        if version >= CURRENT_SCHEMA_VERSION {
+            panic!("Schema already current");
+        }
+
+        // 1 -> 2: add `cancelled` to every call and move its stakes into the
+        // outcome manager's escrow
+        let next_call_id: u64 = instance.get(&DataKey::NextCallId).unwrap_or(0);
+        let cursor: u64 = instance.get(&DataKey::MigrationCursor).unwrap_or(0);
+        let end = next_call_id.min(cursor + u64::from(limit));
+
+        for call_id in cursor..end {
+            let key = DataKey::Call(call_id);
+            if let Some(legacy) = env.storage().persistent().get::<_, CallV1>(&key) {
+                let call = Call {
+                    creator: legacy.creator,
+                    stake_token: legacy.stake_token,
+                    total_stake_yes: legacy.total_stake_yes,
+                    total_stake_no: legacy.total_stake_no,
+                    start_ts: legacy.start_ts,
+                    end_ts: legacy.end_ts,
+                    token_address: legacy.token_address,
+                    pair_id: legacy.pair_id,
+                    ipfs_cid: legacy.ipfs_cid,
+                    settled: legacy.settled,
+                    outcome: legacy.outcome,
+                    final_price: legacy.final_price,
+                    cancelled: false,
+                };
+                env.storage().persistent().set(&key, &call);
+                Self::extend_persistent(&env, &key);
+
+                // Version 1 held stakes here; hand them to the outcome manager
+                let total = call.total_stake_yes + call.total_stake_no;
+                if total > 0 {
+                    let outcome_manager = Self::outcome_manager_client(&env);
+                    token::Client::new(&env, &call.stake_token).transfer(
+                        &env.current_contract_address(),
+                        &outcome_manager.address,
+                        &total,
+                    );
+                    outcome_manager.fund_migrated_call(
+                        &call_id,
+                        &call.stake_token,
+                        &(call.total_stake_yes as u128),
+                        &(call.total_stake_no as u128),
+                        &call.end_ts,
+                    );
+                }
+            }
+        }
+
+        if end < next_call_id {
+            instance.set(&DataKey::MigrationCursor, &end);
+            return version;
+        }
+
+        instance.remove(&DataKey::MigrationCursor);
+        instance.set(&DataKey::SchemaVersion, &CURRENT_SCHEMA_VERSION);
+
+        env.events().publish(
+            (Symbol::new(&env, "SchemaMigrated"),),
+            CURRENT_SCHEMA_VERSION,
+        );
+
+        CURRENT_SCHEMA_VERSION
+    }
+
+    pub fn get_schema_version(env: Env) -> u32 {
+        Self::read_schema_version(&env)
+    }
+
+    /// Propose a new admin (admin only)
+    /// The proposal takes effect once the new admin calls `accept_admin`
+    /// A later proposal replaces a pending one
+    pub fn propose_admin(env: Env, new_admin: Address) {
+        let admin = Self::get_admin(&env);
+        admin.require_auth();
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::PendingAdmin, &new_admin);
+
+        // topics: ["AdminProposed", admin, new_admin]
+        env.events()
+            .publish((Symbol::new(&env, "AdminProposed"), admin, new_admin), ());
+    }
+
+    /// Accept a pending admin proposal (proposed admin only)
+    pub fn accept_admin(env: Env) {
+        let new_admin: Address = env
+            .storage()
+            .persistent()
+            .get(&DataKey::PendingAdmin)
+            .expect("No pending admin");
+        new_admin.require_auth();
+
+        let old_admin = Self::get_admin(&env);
+        env.storage().persistent().set(&DataKey::Admin, &new_admin);
+        env.storage().persistent().remove(&DataKey::PendingAdmin);
+
+        // topics: ["AdminTransferred", old_admin, new_admin]
+        env.events().publish(
+            (Symbol::new(&env, "AdminTransferred"), old_admin, new_admin),
+            (),
+        );
     }
 
-    /// Resume write operations (admin only)
-    pub fn unpause(env: Env) {
+    /// Withdraw a pending admin proposal (admin only)
+    pub fn cancel_admin_proposal(env: Env) {
         let admin = Self::get_admin(&env);
         admin.require_auth();
+
+        let pending: Address = env
+            .storage()
+            .persistent()
+            .get(&DataKey::PendingAdmin)
+            .expect("No pending admin");
+        env.storage().persistent().remove(&DataKey::PendingAdmin);
+
+        // topics: ["AdminProposalCancelled", admin, pending]
+        env.events().publish(
+            (Symbol::new(&env, "AdminProposalCancelled"), admin, pending),
+            (),
+        );
+    }
+
+    pub fn get_admin_view(env: Env) -> Address {
+        Self::get_admin(&env)
+    }
+
+    pub fn get_pending_admin(env: Env) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::PendingAdmin)
+    }
+
+    /// Grant a role to an account (admin only)
+    /// Emits RoleGranted event
+    pub fn grant_role(env: Env, role: Role, account: Address) {
+        let admin = Self::get_admin(&env);
+        admin.require_auth();
+
+        let key = DataKey::Role(role, account.clone());
+        env.storage().persistent().set(&key, &true);
+        Self::extend_persistent(&env, &key);
+
+        // topics: ["RoleGranted", account]
+        // data: role
+        env.events()
+            .publish((Symbol::new(&env, "RoleGranted"), account), role);
+    }
+
+    /// Revoke a role from an account (admin only)
+    /// Emits RoleRevoked event
+    pub fn revoke_role(env: Env, role: Role, account: Address) {
+        let admin = Self::get_admin(&env);
+        admin.require_auth();
+
+        let key = DataKey::Role(role, account.clone());
+        if !env.storage().persistent().has(&key) {
+            panic!("Role not granted");
+        }
+        env.storage().persistent().remove(&key);
+
+        // topics: ["RoleRevoked", account]
+        // data: role
+        env.events()
+            .publish((Symbol::new(&env, "RoleRevoked"), account), role);
+    }
+
+    /// Whether an account may act in a role; the admin holds every role
+    pub fn has_role(env: Env, role: Role, account: Address) -> bool {
+        account == Self::get_admin(&env)
+            || env
+                .storage()
+                .persistent()
+                .has(&DataKey::Role(role, account))
+    }
+
+    /// Pause write operations (admin or pauser)
+    pub fn pause(env: Env, caller: Address) {
+        Self::require_role(&env, Role::Pauser, &caller);
+        env.storage().persistent().set(&DataKey::IsPaused, &true);
+    }
+
+    /// Resume write operations (admin or pauser)
+    pub fn unpause(env: Env, caller: Address) {
+        Self::require_role(&env, Role::Pauser, &caller);
         env.storage().persistent().set(&DataKey::IsPaused, &false);
     }
 
@@ -92,10 +520,119 @@ impl CallRegistry {
         Self::is_paused(&env)
     }
 
+    /// Set the OutcomeManager that calls are registered with (admin only)
+    pub fn set_outcome_manager(env: Env, outcome_manager: Address) {
+        let admin = Self::get_admin(&env);
+        admin.require_auth();
+        env.storage()
+            .persistent()
+            .set(&DataKey::OutcomeManager, &outcome_manager);
+    }
+
+    pub fn get_outcome_manager(env: Env) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::OutcomeManager)
+    }
+
+    /// Set the highest creator fee a call can be created with, in basis
+    /// points (admin only)
+    pub fn set_max_creator_fee(env: Env, basis_points: u32) {
+        let admin = Self::get_admin(&env);
+        admin.require_auth();
+
+        if basis_points > 10_000 {
+            panic!("Fee basis points cannot exceed 10000");
+        }
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::MaxCreatorFee, &basis_points);
+    }
+
+    pub fn get_max_creator_fee_view(env: Env) -> u32 {
+        Self::get_max_creator_fee(&env)
+    }
+
+    /// Set the stake limits applied to every call (admin only)
+    /// Creators can only tighten these for their own calls
+    pub fn set_stake_limits(env: Env, limits: StakeLimits) {
+        let admin = Self::get_admin(&env);
+        admin.require_auth();
+
+        Self::validate_stake_limits(&limits);
+        env.storage()
+            .persistent()
+            .set(&DataKey::StakeLimits, &limits);
+    }
+
+    pub fn get_stake_limits_view(env: Env) -> StakeLimits {
+        Self::get_stake_limits(&env)
+    }
+
+    /// Allow calls to be created and staked in a token, or update its limits
+    /// (admin only)
+    /// Each stake in the token must be between `min_stake` and `max_stake`
+    /// Emits StakeTokenSet event
+    pub fn set_stake_token(env: Env, stake_token: Address, min_stake: i128, max_stake: i128) {
+        let admin = Self::get_admin(&env);
+        admin.require_auth();
+
+        if min_stake <= 0 || max_stake < min_stake {
+            panic!("Invalid stake limits");
+        }
+
+        let key = DataKey::StakeToken(stake_token.clone());
+        env.storage().persistent().set(
+            &key,
+            &StakeTokenLimits {
+                min_stake,
+                max_stake,
+            },
+        );
+        Self::extend_persistent(&env, &key);
+
+        // topics: ["StakeTokenSet", stake_token]
+        // data: (min_stake, max_stake)
+        env.events().publish(
+            (Symbol::new(&env, "StakeTokenSet"), stake_token),
+            (min_stake, max_stake),
+        );
+    }
+
+    /// Remove a token from the allowlist (admin only)
+    /// Existing calls in the token can still be claimed but take no new stakes
+    /// Emits StakeTokenRemoved event
+    pub fn remove_stake_token(env: Env, stake_token: Address) {
+        let admin = Self::get_admin(&env);
+        admin.require_auth();
+
+        let key = DataKey::StakeToken(stake_token.clone());
+        if !env.storage().persistent().has(&key) {
+            panic!("Stake token not allowed");
+        }
+        env.storage().persistent().remove(&key);
+
+        // topics: ["StakeTokenRemoved", stake_token]
+        env.events()
+            .publish((Symbol::new(&env, "StakeTokenRemoved"), stake_token), ());
+    }
+
+    /// Get a token's stake limits, or None if it is not allowlisted
+    pub fn get_stake_token(env: Env, stake_token: Address) -> Option<StakeTokenLimits> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::StakeToken(stake_token))
+    }
+
     /// Create a new prediction call
-    /// Accepts creator, stake token, stake amount, end timestamp, and call metadata
-    /// Transfers stake from creator to contract (escrow)
+    /// Accepts creator, stake token, stake amount, end timestamp, call metadata
+    /// and the creator fee
+    /// The stake token must be allowlisted and the stake within its limits
+    /// The creator fee is basis points of the losing pool paid to the
+    /// creator if the call resolves YES, capped by the admin's max creator fee
+    /// The creator's stake must be within the global stake limits
+    /// Transfers stake from creator to the OutcomeManager, which holds the escrow
     /// Stores call data in persistent storage
+    /// Registers the call and its creator fee with the OutcomeManager
     /// Emits CallCreated event
     /// Returns the new call ID
     pub fn create_call(
@@ -105,8 +642,10 @@ impl CallRegistry {
         stake_amount: i128,
         end_ts: u64,
         metadata: CreateCallMetadata,
+        creator_fee_bps: u32,
     ) -> u64 {
         Self::assert_not_paused(&env);
+        Self::assert_schema_current(&env);
         creator.require_auth();
 
         if end_ts <= env.ledger().timestamp() {
@@ -115,10 +654,16 @@ impl CallRegistry {
         if stake_amount <= 0 {
             panic!("Stake amount must be > 0");
         }
+        Self::check_stake_token(&env, &stake_token, stake_amount);
+        if creator_fee_bps > Self::get_max_creator_fee(&env) {
+            panic!("Creator fee exceeds max");
+        }
+
+        let outcome_manager = Self::outcome_manager_client(&env);
 
-        // Transfer stake from creator to contract
+        // Transfer stake from creator to the outcome manager escrow
         let token_client = token::Client::new(&env, &stake_token);
-        token_client.transfer(&creator, &env.current_contract_address(), &stake_amount);
+        token_client.transfer(&creator, &outcome_manager.address, &stake_amount);
 
         // Get and increment ID
         let call_id = env
@@ -145,18 +690,29 @@ impl CallRegistry {
             settled: false,
             outcome: false,
             final_price: 0,
+            cancelled: false,
         };
 
+        Self::check_stake_limits(&env, call_id, stake_amount, stake_amount, stake_amount);
+
         // Store call
-        env.storage()
-            .persistent()
-            .set(&DataKey::Call(call_id), &call);
+        let key = DataKey::Call(call_id);
+        env.storage().persistent().set(&key, &call);
+        Self::extend_persistent(&env, &key);
 
         // Record creator's stake (YES position)
-        env.storage().persistent().set(
-            &DataKey::UserStake(call_id, creator.clone(), true),
-            &stake_amount,
-        );
+        let stake_key = DataKey::UserStake(call_id, creator.clone(), true);
+        env.storage().persistent().set(&stake_key, &stake_amount);
+        Self::extend_persistent(&env, &stake_key);
+
+        outcome_manager.register_call(&call_id, &stake_token, &(stake_amount as u128), &0, &end_ts);
+
+        if creator_fee_bps > 0 {
+            let fee_key = DataKey::CreatorFee(call_id);
+            env.storage().persistent().set(&fee_key, &creator_fee_bps);
+            Self::extend_persistent(&env, &fee_key);
+            outcome_manager.set_creator_fee(&call_id, &creator, &creator_fee_bps);
+        }
 
         // Emit CallCreated event
         // topics: ["CallCreated", call_id, creator]
@@ -180,11 +736,16 @@ impl CallRegistry {
     /// Stake on an existing call
     /// Accepts call ID, staker, amount, and position (true=YES, false=NO)
     /// Validates call exists, hasn't ended, and isn't settled
-    /// Transfers stake to contract
+    /// Validates the amount against the stake token's limits
+    /// Validates the staker's total across both positions and the call's pool
+    /// against the global and per-call stake limits
+    /// Transfers stake to the OutcomeManager escrow
     /// Updates total_stake_yes or total_stake_no
+    /// Syncs the new stake to the OutcomeManager
     /// Emits StakeAdded event
     pub fn stake_on_call(env: Env, call_id: u64, staker: Address, amount: i128, position: bool) {
         Self::assert_not_paused(&env);
+        Self::assert_schema_current(&env);
         staker.require_auth();
 
         let key = DataKey::Call(call_id);
@@ -200,13 +761,35 @@ impl CallRegistry {
         if call.settled {
             panic!("Call settled");
         }
+        if call.cancelled {
+            panic!("Call cancelled");
+        }
         if amount <= 0 {
             panic!("Amount must be > 0");
         }
+        Self::check_stake_token(&env, &call.stake_token, amount);
+
+        let user_total: i128 = [true, false]
+            .into_iter()
+            .map(|side| {
+                env.storage()
+                    .persistent()
+                    .get::<_, i128>(&DataKey::UserStake(call_id, staker.clone(), side))
+                    .unwrap_or(0)
+            })
+            .sum();
+        Self::check_stake_limits(
+            &env,
+            call_id,
+            amount,
+            user_total + amount,
+            call.total_stake_yes + call.total_stake_no + amount,
+        );
 
-        // Transfer stake
+        // Transfer stake to the outcome manager escrow
+        let outcome_manager = Self::outcome_manager_client(&env);
         let token_client = token::Client::new(&env, &call.stake_token);
-        token_client.transfer(&staker, &env.current_contract_address(), &amount);
+        token_client.transfer(&staker, &outcome_manager.address, &amount);
 
         // Update totals
         if position {
@@ -215,6 +798,7 @@ impl CallRegistry {
             call.total_stake_no += amount;
         }
         env.storage().persistent().set(&key, &call);
+        Self::extend_persistent(&env, &key);
 
         // Update user stake
         let stake_key = DataKey::UserStake(call_id, staker.clone(), position);
@@ -222,6 +806,9 @@ impl CallRegistry {
         env.storage()
             .persistent()
             .set(&stake_key, &(current_stake + amount));
+        Self::extend_persistent(&env, &stake_key);
+
+        outcome_manager.record_stake(&call_id, &position, &(amount as u128));
 
         // Emit StakeAdded event
         // topics: ["StakeAdded", call_id, staker]
@@ -232,19 +819,272 @@ impl CallRegistry {
         );
     }
 
+    /// Set stake limits for a call before anyone else has staked on it
+    /// (creator only)
+    /// They apply on top of the global stake limits and must hold for the
+    /// creator's own stake
+    /// Emits CallStakeLimitsSet event
+    pub fn set_call_stake_limits(env: Env, call_id: u64, limits: StakeLimits) {
+        Self::assert_not_paused(&env);
+        Self::assert_schema_current(&env);
+
+        let key = DataKey::Call(call_id);
+        let call: Call = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .expect("Call does not exist");
+
+        call.creator.require_auth();
+
+        if call.settled {
+            panic!("Call settled");
+        }
+        if call.cancelled {
+            panic!("Call cancelled");
+        }
+        Self::validate_stake_limits(&limits);
+
+        let creator_stake: i128 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::UserStake(call_id, call.creator.clone(), true))
+            .unwrap_or(0);
+        if call.total_stake_no != 0 || call.total_stake_yes != creator_stake {
+            panic!("Call has other stakers");
+        }
+
+        let limits_key = DataKey::CallStakeLimits(call_id);
+        env.storage().persistent().set(&limits_key, &limits);
+        Self::extend_persistent(&env, &limits_key);
+        Self::check_stake_limits(&env, call_id, creator_stake, creator_stake, creator_stake);
+
+        // Emit CallStakeLimitsSet event
+        // topics: ["CallStakeLimitsSet", call_id, creator]
+        // data: limits
+        env.events().publish(
+            (
+                Symbol::new(&env, "CallStakeLimitsSet"),
+                call_id,
+                call.creator,
+            ),
+            limits,
+        );
+    }
+
+    /// Cancel a call before anyone else has staked on it (creator only)
+    /// Validates the call isn't settled or cancelled and that the creator's
+    /// YES stake is the only stake on the call
+    /// Marks the call cancelled so no further stakes are accepted
+    /// Has the OutcomeManager refund the creator's escrowed stake
+    /// Emits CallCancelled event
+    pub fn cancel_call(env: Env, call_id: u64) {
+        Self::assert_not_paused(&env);
+        Self::assert_schema_current(&env);
+
+        let key = DataKey::Call(call_id);
+        let mut call: Call = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .expect("Call does not exist");
+
+        call.creator.require_auth();
+
+        if call.settled {
+            panic!("Call settled");
+        }
+        if call.cancelled {
+            panic!("Call cancelled");
+        }
+
+        let creator_stake: i128 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::UserStake(call_id, call.creator.clone(), true))
+            .unwrap_or(0);
+        if call.total_stake_no != 0 || call.total_stake_yes != creator_stake {
+            panic!("Call has other stakers");
+        }
+
+        call.cancelled = true;
+        env.storage().persistent().set(&key, &call);
+        Self::extend_persistent(&env, &key);
+
+        let outcome_manager = Self::outcome_manager_client(&env);
+        outcome_manager.cancel_call(&call_id, &call.creator, &(creator_stake as u128));
+
+        // Emit CallCancelled event
+        // topics: ["CallCancelled", call_id, creator]
+        // data: refunded stake
+        env.events().publish(
+            (Symbol::new(&env, "CallCancelled"), call_id, call.creator),
+            creator_stake,
+        );
+    }
+
+    /// Claim a user's winnings from a settled call
+    /// Pays both the user's YES and NO stakes through the OutcomeManager,
+    /// which applies the outcome, fee and escrow checks
+    /// Clears the user's stakes and records the final outcome on the call
+    /// Emits PayoutClaimed event
+    /// Returns the payout net of fees
+    pub fn claim(env: Env, call_id: u64, user: Address) -> i128 {
+        Self::assert_not_paused(&env);
+        Self::assert_schema_current(&env);
+        user.require_auth();
+
+        let key = DataKey::Call(call_id);
+        let mut call: Call = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .expect("Call does not exist");
+
+        let yes_key = DataKey::UserStake(call_id, user.clone(), true);
+        let no_key = DataKey::UserStake(call_id, user.clone(), false);
+        let stake_yes: i128 = env.storage().persistent().get(&yes_key).unwrap_or(0);
+        let stake_no: i128 = env.storage().persistent().get(&no_key).unwrap_or(0);
+        if stake_yes == 0 && stake_no == 0 {
+            panic!("Nothing to claim");
+        }
+
+        let outcome_manager = Self::outcome_manager_client(&env);
+        let payout = outcome_manager.claim_payout(&call_id, &user, &stake_yes, &stake_no) as i128;
+
+        env.storage().persistent().remove(&yes_key);
+        env.storage().persistent().remove(&no_key);
+
+        // Pull the final outcome the payout was made under
+        if !call.settled {
+            let call_data = outcome_manager
+                .get_call(&call_id)
+                .expect("Call not registered");
+            if let (true, Some(outcome), Some(final_price)) = (
+                call_data.finalized,
+                call_data.outcome,
+                call_data.final_price,
+            ) {
+                call.settled = true;
+                call.outcome = outcome;
+                call.final_price = final_price as i128;
+                env.storage().persistent().set(&key, &call);
+                Self::extend_persistent(&env, &key);
+            }
+        }
+
+        // Emit PayoutClaimed event
+        // topics: ["PayoutClaimed", call_id, user]
+        // data: payout
+        env.events()
+            .publish((Symbol::new(&env, "PayoutClaimed"), call_id, user), payout);
+
+        payout
+    }
+
     pub fn get_call(env: Env, call_id: u64) -> Call {
+        let key = DataKey::Call(call_id);
+        let call = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .expect("Call does not exist");
+        Self::extend_persistent(&env, &key);
+        call
+    }
+
+    /// Get the stake limits the creator set for a call, if any
+    pub fn get_call_stake_limits(env: Env, call_id: u64) -> Option<StakeLimits> {
         env.storage()
             .persistent()
-            .get(&DataKey::Call(call_id))
-            .expect("Call does not exist")
+            .get(&DataKey::CallStakeLimits(call_id))
     }
 
-    pub fn get_user_stake(env: Env, call_id: u64, user: Address, position: bool) -> i128 {
+    /// Creator fee of a call in basis points of the losing pool (0 if none)
+    pub fn get_creator_fee(env: Env, call_id: u64) -> u32 {
         env.storage()
             .persistent()
-            .get(&DataKey::UserStake(call_id, user, position))
+            .get(&DataKey::CreatorFee(call_id))
             .unwrap_or(0)
     }
+
+    pub fn get_user_stake(env: Env, call_id: u64, user: Address, position: bool) -> i128 {
+        let key = DataKey::UserStake(call_id, user, position);
+        match env.storage().persistent().get(&key) {
+            Some(stake) => {
+                Self::extend_persistent(&env, &key);
+                stake
+            }
+            None => 0,
+        }
+    }
+
+    /// Extend the TTL of a call, its creator's stake and fee, and the outcome
+    /// manager's record of it
+    /// Anyone can pay to keep a call from being archived
+    pub fn bump_call(env: Env, call_id: u64) {
+        let key = DataKey::Call(call_id);
+        let call: Call = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .expect("Call does not exist");
+        Self::extend_persistent(&env, &key);
+
+        for key in [
+            DataKey::UserStake(call_id, call.creator, true),
+            DataKey::CreatorFee(call_id),
+            DataKey::CallStakeLimits(call_id),
+        ] {
+            if env.storage().persistent().has(&key) {
+                Self::extend_persistent(&env, &key);
+            }
+        }
+
+        Self::outcome_manager_client(&env).bump_call(&call_id);
+    }
+
+    /// Extend the TTL of a user's YES and NO stakes on a call
+    /// `bump_call` only covers the creator's stake, so anyone can use this to
+    /// keep other stakers' entries from being archived on a long-running call
+    pub fn bump_stake(env: Env, call_id: u64, user: Address) {
+        let mut bumped = false;
+        for position in [true, false] {
+            let key = DataKey::UserStake(call_id, user.clone(), position);
+            if env.storage().persistent().has(&key) {
+                Self::extend_persistent(&env, &key);
+                bumped = true;
+            }
+        }
+        if !bumped {
+            panic!("No stake to bump");
+        }
+    }
+
+    /// Set the TTL threshold and extension applied to stored entries (admin only)
+    pub fn set_ttl_config(env: Env, threshold: u32, extend_to: u32) {
+        let admin = Self::get_admin(&env);
+        admin.require_auth();
+
+        if threshold == 0 || threshold >= extend_to {
+            panic!("Threshold must be > 0 and below extend_to");
+        }
+        if extend_to > env.storage().max_ttl() {
+            panic!("extend_to exceeds max TTL");
+        }
+
+        env.storage().instance().set(
+            &DataKey::TtlConfig,
+            &TtlConfig {
+                threshold,
+                extend_to,
+            },
+        );
+    }
+
+    pub fn get_ttl_config_view(env: Env) -> TtlConfig {
+        Self::get_ttl_config(&env)
+    }
 }
 
 mod test;
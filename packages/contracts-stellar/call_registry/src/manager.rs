@@ -0,0 +1,50 @@
+use soroban_sdk::{contractclient, contracttype, Address, Env};
+
+/// Mirror of `outcome_manager::CallData`.
+/// Field names and types must match the outcome manager's definition so the
+/// value decodes across the contract boundary.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct CallData {
+    pub id: u64,
+    pub token: Address,
+    pub long_tokens: u128,
+    pub short_tokens: u128,
+    pub end_ts: u64,
+    pub settled: bool,
+    pub outcome: Option<bool>,
+    pub final_price: Option<u128>,
+    pub escrow: u128,
+    pub dispute_deadline: u64,
+    pub finalized: bool,
+    pub voided: bool,
+    pub cancelled: bool,
+}
+
+/// Subset of the OutcomeManager interface used by the registry
+#[contractclient(name = "OutcomeManagerClient")]
+pub trait OutcomeManagerInterface {
+    fn register_call(
+        env: Env,
+        call_id: u64,
+        token: Address,
+        long_tokens: u128,
+        short_tokens: u128,
+        end_ts: u64,
+    );
+    fn fund_migrated_call(
+        env: Env,
+        call_id: u64,
+        token: Address,
+        long_tokens: u128,
+        short_tokens: u128,
+        end_ts: u64,
+    );
+    fn set_creator_fee(env: Env, call_id: u64, creator: Address, basis_points: u32);
+    fn record_stake(env: Env, call_id: u64, position: bool, amount: u128);
+    fn cancel_call(env: Env, call_id: u64, creator: Address, amount: u128);
+    fn claim_payout(env: Env, call_id: u64, user: Address, stake_yes: i128, stake_no: i128)
+        -> u128;
+    fn get_call(env: Env, call_id: u64) -> Option<CallData>;
+    fn bump_call(env: Env, call_id: u64);
+}
@@ -1,11 +1,25 @@
 #![cfg(test)]
 
 use super::*;
+use outcome_manager::{
+    CallData as ManagerCallData, DataKey as ManagerKey, OutcomeManagerContract,
+    OutcomeManagerContractClient,
+};
 use soroban_sdk::{
-    testutils::{Address as _, Events, Ledger, MockAuth, MockAuthInvoke},
-    Address, BytesN, Env, IntoVal, String,
+    testutils::{
+        storage::{Instance as _, Persistent as _},
+        Address as _, Events, Ledger, MockAuth, MockAuthInvoke,
+    },
+    Address, Bytes, BytesN, Env, IntoVal, String,
 };
 
+fn register_outcome_manager(env: &Env, client: &CallRegistryClient, admin: &Address) -> Address {
+    let outcome_manager_id = env.register_contract(None, OutcomeManagerContract);
+    OutcomeManagerContractClient::new(env, &outcome_manager_id).initialize(admin, &client.address);
+    client.set_outcome_manager(&outcome_manager_id);
+    outcome_manager_id
+}
+
 #[test]
 fn test_create_call() {
     let env = Env::default();
@@ -15,6 +29,8 @@ fn test_create_call() {
     let client = CallRegistryClient::new(&env, &contract_id);
     let admin = Address::generate(&env);
     client.initialize(&admin);
+    let outcome_manager_id = register_outcome_manager(&env, &client, &admin);
+    let outcome_manager = OutcomeManagerContractClient::new(&env, &outcome_manager_id);
 
     let creator = Address::generate(&env);
     let stake_token_admin = Address::generate(&env);
@@ -36,7 +52,8 @@ fn test_create_call() {
         ipfs_cid: ipfs_cid.clone(),
     };
 
-    let call_id = client.create_call(&creator, &stake_token, &100, &end_ts, &metadata);
+    client.set_stake_token(&stake_token, &1, &1_000_000);
+    let call_id = client.create_call(&creator, &stake_token, &100, &end_ts, &metadata, &0);
 
     assert_eq!(call_id, 0);
 
@@ -50,9 +67,18 @@ fn test_create_call() {
     let stake = client.get_user_stake(&call_id, &creator, &true);
     assert_eq!(stake, 100);
 
+    // Check the call was registered with the outcome manager
+    let call_data = outcome_manager.get_call(&call_id).unwrap();
+    assert_eq!(call_data.token, stake_token);
+    assert_eq!(call_data.long_tokens, 100);
+    assert_eq!(call_data.short_tokens, 0);
+    assert_eq!(call_data.end_ts, end_ts);
+
     // Check token transfer
     assert_eq!(stake_token_client.balance(&creator), 900);
-    assert_eq!(stake_token_client.balance(&contract_id), 100);
+    assert_eq!(stake_token_client.balance(&contract_id), 0);
+    assert_eq!(stake_token_client.balance(&outcome_manager_id), 100);
+    assert_eq!(call_data.escrow, 100);
 
     // Check events
     let events = env.events().all();
@@ -73,6 +99,7 @@ fn test_stake_on_call() {
     let client = CallRegistryClient::new(&env, &contract_id);
     let admin = Address::generate(&env);
     client.initialize(&admin);
+    let outcome_manager_id = register_outcome_manager(&env, &client, &admin);
 
     let creator = Address::generate(&env);
     let staker = Address::generate(&env);
@@ -95,7 +122,8 @@ fn test_stake_on_call() {
         ipfs_cid: ipfs_cid.clone(),
     };
 
-    let call_id = client.create_call(&creator, &stake_token, &100, &end_ts, &metadata);
+    client.set_stake_token(&stake_token, &1, &1_000_000);
+    let call_id = client.create_call(&creator, &stake_token, &100, &end_ts, &metadata, &0);
 
     // Stake NO
     client.stake_on_call(&call_id, &staker, &50, &false);
@@ -108,7 +136,32 @@ fn test_stake_on_call() {
     assert_eq!(staker_stake, 50);
 
     assert_eq!(stake_token_client.balance(&staker), 950);
-    assert_eq!(stake_token_client.balance(&contract_id), 150);
+    assert_eq!(stake_token_client.balance(&contract_id), 0);
+    assert_eq!(stake_token_client.balance(&outcome_manager_id), 150);
+}
+
+#[test]
+#[should_panic(expected = "Outcome manager not set")]
+fn test_create_call_without_outcome_manager() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, CallRegistry);
+    let client = CallRegistryClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let creator = Address::generate(&env);
+    let stake_token = Address::generate(&env);
+
+    let end_ts = env.ledger().timestamp() + 1000;
+    let metadata = CreateCallMetadata {
+        token_address: Address::generate(&env),
+        pair_id: BytesN::from_array(&env, &[0; 32]),
+        ipfs_cid: String::from_str(&env, "QmHash"),
+    };
+
+    client.set_stake_token(&stake_token, &1, &1_000_000);
+    client.create_call(&creator, &stake_token, &100, &end_ts, &metadata, &0);
 }
 
 #[test]
@@ -135,7 +188,8 @@ fn test_create_call_past_end_time() {
         ipfs_cid,
     };
 
-    client.create_call(&creator, &stake_token, &100, &end_ts, &metadata);
+    client.set_stake_token(&stake_token, &1, &1_000_000);
+    client.create_call(&creator, &stake_token, &100, &end_ts, &metadata, &0);
 }
 
 #[test]
@@ -148,6 +202,7 @@ fn test_stake_ended_call() {
     let client = CallRegistryClient::new(&env, &contract_id);
     let admin = Address::generate(&env);
     client.initialize(&admin);
+    register_outcome_manager(&env, &client, &admin);
 
     let creator = Address::generate(&env);
     let staker = Address::generate(&env);
@@ -170,7 +225,8 @@ fn test_stake_ended_call() {
         ipfs_cid,
     };
 
-    let call_id = client.create_call(&creator, &stake_token, &100, &end_ts, &metadata);
+    client.set_stake_token(&stake_token, &1, &1_000_000);
+    let call_id = client.create_call(&creator, &stake_token, &100, &end_ts, &metadata, &0);
 
     // Fast forward time
     env.ledger().set_timestamp(end_ts + 1);
@@ -195,7 +251,7 @@ fn test_create_call_paused() {
     let stake_token_admin_client = token::StellarAssetClient::new(&env, &stake_token);
 
     client.initialize(&admin);
-    client.pause();
+    client.pause(&admin);
 
     stake_token_admin_client.mint(&creator, &1000);
 
@@ -209,7 +265,8 @@ fn test_create_call_paused() {
         ipfs_cid,
     };
 
-    client.create_call(&creator, &stake_token, &100, &end_ts, &metadata);
+    client.set_stake_token(&stake_token, &1, &1_000_000);
+    client.create_call(&creator, &stake_token, &100, &end_ts, &metadata, &0);
 }
 
 #[test]
@@ -229,6 +286,7 @@ fn test_pause_unpause_flow() {
     let stake_token_admin_client = token::StellarAssetClient::new(&env, &stake_token);
 
     client.initialize(&admin);
+    register_outcome_manager(&env, &client, &admin);
     assert!(!client.get_is_paused());
 
     stake_token_admin_client.mint(&creator, &1000);
@@ -244,12 +302,13 @@ fn test_pause_unpause_flow() {
         ipfs_cid,
     };
 
-    let call_id = client.create_call(&creator, &stake_token, &100, &end_ts, &metadata);
+    client.set_stake_token(&stake_token, &1, &1_000_000);
+    let call_id = client.create_call(&creator, &stake_token, &100, &end_ts, &metadata, &0);
 
-    client.pause();
+    client.pause(&admin);
     assert!(client.get_is_paused());
 
-    client.unpause();
+    client.unpause(&admin);
     assert!(!client.get_is_paused());
 
     client.stake_on_call(&call_id, &staker, &50, &false);
@@ -281,11 +340,11 @@ fn test_pause_requires_admin_auth() {
         invoke: &MockAuthInvoke {
             contract: &contract_id,
             fn_name: "pause",
-            args: ().into_val(&env),
+            args: (attacker.clone(),).into_val(&env),
             sub_invokes: &[],
         },
     }]);
-    client.pause();
+    client.pause(&attacker);
 }
 
 #[test]
@@ -314,20 +373,1152 @@ fn test_unpause_requires_admin_auth() {
         invoke: &MockAuthInvoke {
             contract: &contract_id,
             fn_name: "pause",
-            args: ().into_val(&env),
+            args: (admin.clone(),).into_val(&env),
             sub_invokes: &[],
         },
     }]);
-    client.pause();
+    client.pause(&admin);
 
     env.mock_auths(&[MockAuth {
         address: &attacker,
         invoke: &MockAuthInvoke {
             contract: &contract_id,
             fn_name: "unpause",
-            args: ().into_val(&env),
+            args: (attacker.clone(),).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    client.unpause(&attacker);
+}
+
+/// Registers a registry wired to an outcome manager and opens a call with a
+/// 100 YES stake from `creator`; `creator` and `staker` are minted 1000 each
+fn setup_open_call<'a>(
+    env: &Env,
+    creator: &Address,
+    staker: &Address,
+) -> (CallRegistryClient<'a>, Address, Address, u64) {
+    let contract_id = env.register_contract(None, CallRegistry);
+    let client = CallRegistryClient::new(env, &contract_id);
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    let outcome_manager_id = register_outcome_manager(env, &client, &admin);
+
+    let stake_token_admin = Address::generate(env);
+    let stake_token = env
+        .register_stellar_asset_contract_v2(stake_token_admin)
+        .address();
+    let stake_token_admin_client = token::StellarAssetClient::new(env, &stake_token);
+    stake_token_admin_client.mint(creator, &1000);
+    stake_token_admin_client.mint(staker, &1000);
+
+    let end_ts = env.ledger().timestamp() + 1000;
+    let metadata = CreateCallMetadata {
+        token_address: Address::generate(env),
+        pair_id: BytesN::from_array(env, &[0; 32]),
+        ipfs_cid: String::from_str(env, "QmHash"),
+    };
+    client.set_stake_token(&stake_token, &1, &1_000_000);
+    let call_id = client.create_call(creator, &stake_token, &100, &end_ts, &metadata, &0);
+
+    (client, outcome_manager_id, stake_token, call_id)
+}
+
+#[test]
+fn test_create_call_with_creator_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, CallRegistry);
+    let client = CallRegistryClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let outcome_manager_id = register_outcome_manager(&env, &client, &admin);
+    let outcome_manager = OutcomeManagerContractClient::new(&env, &outcome_manager_id);
+
+    let creator = Address::generate(&env);
+    let stake_token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    token::StellarAssetClient::new(&env, &stake_token).mint(&creator, &1000);
+
+    assert_eq!(client.get_max_creator_fee_view(), 0);
+    client.set_max_creator_fee(&1_000);
+    assert_eq!(client.get_max_creator_fee_view(), 1_000);
+
+    let end_ts = env.ledger().timestamp() + 1000;
+    let metadata = CreateCallMetadata {
+        token_address: Address::generate(&env),
+        pair_id: BytesN::from_array(&env, &[0; 32]),
+        ipfs_cid: String::from_str(&env, "QmHash"),
+    };
+    client.set_stake_token(&stake_token, &1, &1_000_000);
+    let with_fee = client.create_call(&creator, &stake_token, &100, &end_ts, &metadata, &500);
+    let without_fee = client.create_call(&creator, &stake_token, &100, &end_ts, &metadata, &0);
+
+    assert_eq!(client.get_creator_fee(&with_fee), 500);
+    assert_eq!(client.get_creator_fee(&without_fee), 0);
+
+    // The outcome manager pays the fee to the creator
+    let creator_fee = outcome_manager.get_creator_fee(&with_fee).unwrap();
+    assert_eq!(creator_fee.creator, creator);
+    assert_eq!(creator_fee.basis_points, 500);
+    assert!(outcome_manager.get_creator_fee(&without_fee).is_none());
+}
+
+#[test]
+#[should_panic(expected = "Creator fee exceeds max")]
+fn test_create_call_creator_fee_above_max() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, CallRegistry);
+    let client = CallRegistryClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    register_outcome_manager(&env, &client, &admin);
+    client.set_max_creator_fee(&1_000);
+
+    let creator = Address::generate(&env);
+    let stake_token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    token::StellarAssetClient::new(&env, &stake_token).mint(&creator, &1000);
+
+    let end_ts = env.ledger().timestamp() + 1000;
+    let metadata = CreateCallMetadata {
+        token_address: Address::generate(&env),
+        pair_id: BytesN::from_array(&env, &[0; 32]),
+        ipfs_cid: String::from_str(&env, "QmHash"),
+    };
+    client.set_stake_token(&stake_token, &1, &1_000_000);
+    client.create_call(&creator, &stake_token, &100, &end_ts, &metadata, &1_001);
+}
+
+#[test]
+#[should_panic]
+fn test_set_max_creator_fee_requires_admin_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, CallRegistry);
+    let client = CallRegistryClient::new(&env, &contract_id);
+    let attacker = Address::generate(&env);
+    client.initialize(&Address::generate(&env));
+
+    env.mock_auths(&[MockAuth {
+        address: &attacker,
+        invoke: &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "set_max_creator_fee",
+            args: (10_000u32,).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    client.set_max_creator_fee(&10_000);
+}
+
+#[test]
+#[should_panic(expected = "Stake token not allowed")]
+fn test_create_call_with_unlisted_token() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, CallRegistry);
+    let client = CallRegistryClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    register_outcome_manager(&env, &client, &admin);
+
+    let creator = Address::generate(&env);
+    let stake_token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    token::StellarAssetClient::new(&env, &stake_token).mint(&creator, &1000);
+
+    let end_ts = env.ledger().timestamp() + 1000;
+    let metadata = CreateCallMetadata {
+        token_address: Address::generate(&env),
+        pair_id: BytesN::from_array(&env, &[0; 32]),
+        ipfs_cid: String::from_str(&env, "QmHash"),
+    };
+    client.create_call(&creator, &stake_token, &100, &end_ts, &metadata, &0);
+}
+
+#[test]
+fn test_stake_token_limits() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let creator = Address::generate(&env);
+    let staker = Address::generate(&env);
+    let (client, _, stake_token, call_id) = setup_open_call(&env, &creator, &staker);
+
+    client.set_stake_token(&stake_token, &50, &500);
+    let limits = client.get_stake_token(&stake_token).unwrap();
+    assert_eq!(limits.min_stake, 50);
+    assert_eq!(limits.max_stake, 500);
+
+    let end_ts = env.ledger().timestamp() + 1000;
+    let metadata = CreateCallMetadata {
+        token_address: Address::generate(&env),
+        pair_id: BytesN::from_array(&env, &[0; 32]),
+        ipfs_cid: String::from_str(&env, "QmHash"),
+    };
+    assert!(client
+        .try_create_call(&creator, &stake_token, &49, &end_ts, &metadata, &0)
+        .is_err());
+    assert!(client
+        .try_create_call(&creator, &stake_token, &501, &end_ts, &metadata, &0)
+        .is_err());
+    assert!(client
+        .try_stake_on_call(&call_id, &staker, &49, &false)
+        .is_err());
+    assert!(client
+        .try_stake_on_call(&call_id, &staker, &501, &false)
+        .is_err());
+
+    client.stake_on_call(&call_id, &staker, &500, &false);
+    assert_eq!(client.get_user_stake(&call_id, &staker, &false), 500);
+
+    // A delisted token takes no new stakes
+    client.remove_stake_token(&stake_token);
+    assert!(client.get_stake_token(&stake_token).is_none());
+    assert!(client
+        .try_stake_on_call(&call_id, &staker, &100, &false)
+        .is_err());
+    assert!(client.try_set_stake_token(&stake_token, &0, &500).is_err());
+    assert!(client.try_set_stake_token(&stake_token, &50, &49).is_err());
+}
+
+#[test]
+#[should_panic]
+fn test_set_stake_token_requires_admin_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, CallRegistry);
+    let client = CallRegistryClient::new(&env, &contract_id);
+    let attacker = Address::generate(&env);
+    let stake_token = Address::generate(&env);
+    client.initialize(&Address::generate(&env));
+
+    env.mock_auths(&[MockAuth {
+        address: &attacker,
+        invoke: &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "set_stake_token",
+            args: (stake_token.clone(), 1i128, i128::MAX).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    client.set_stake_token(&stake_token, &1, &i128::MAX);
+}
+
+#[test]
+fn test_cancel_call() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let creator = Address::generate(&env);
+    let staker = Address::generate(&env);
+    let (client, outcome_manager_id, stake_token, call_id) =
+        setup_open_call(&env, &creator, &staker);
+    let stake_token_client = token::Client::new(&env, &stake_token);
+
+    client.cancel_call(&call_id);
+
+    let last_event = env.events().all().last().unwrap();
+    let symbol: Symbol = last_event.1.get(0).unwrap().into_val(&env);
+    assert_eq!(symbol, Symbol::new(&env, "CallCancelled"));
+    let refunded: i128 = last_event.2.into_val(&env);
+    assert_eq!(refunded, 100);
+
+    assert!(client.get_call(&call_id).cancelled);
+    assert_eq!(stake_token_client.balance(&creator), 1000);
+    assert_eq!(stake_token_client.balance(&outcome_manager_id), 0);
+
+    let outcome_manager = OutcomeManagerContractClient::new(&env, &outcome_manager_id);
+    let call_data = outcome_manager.get_call(&call_id).unwrap();
+    assert!(call_data.cancelled);
+    assert_eq!(call_data.escrow, 0);
+    assert!(outcome_manager.has_withdrawn(&call_id, &creator));
+}
+
+#[test]
+#[should_panic(expected = "Already withdrawn")]
+fn test_cancel_call_after_unopposed_refund() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let creator = Address::generate(&env);
+    let staker = Address::generate(&env);
+    let (client, outcome_manager_id, _, call_id) = setup_open_call(&env, &creator, &staker);
+    let outcome_manager = OutcomeManagerContractClient::new(&env, &outcome_manager_id);
+
+    // Nobody took the other side, so the creator is refunded after end_ts
+    env.ledger().set_timestamp(client.get_call(&call_id).end_ts);
+    assert_eq!(outcome_manager.withdraw_payout(&call_id, &creator), 100);
+
+    client.cancel_call(&call_id);
+}
+
+#[test]
+#[should_panic(expected = "Call cancelled")]
+fn test_stake_on_cancelled_call() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let creator = Address::generate(&env);
+    let staker = Address::generate(&env);
+    let (client, _, _, call_id) = setup_open_call(&env, &creator, &staker);
+
+    client.cancel_call(&call_id);
+    client.stake_on_call(&call_id, &staker, &50, &false);
+}
+
+#[test]
+#[should_panic(expected = "Call has other stakers")]
+fn test_cancel_call_with_other_stakers() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let creator = Address::generate(&env);
+    let staker = Address::generate(&env);
+    let (client, _, _, call_id) = setup_open_call(&env, &creator, &staker);
+
+    // A stake on the creator's own side still counts as another staker
+    client.stake_on_call(&call_id, &staker, &50, &true);
+    client.cancel_call(&call_id);
+}
+
+#[test]
+#[should_panic(expected = "Call cancelled")]
+fn test_cancel_call_twice() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let creator = Address::generate(&env);
+    let staker = Address::generate(&env);
+    let (client, _, _, call_id) = setup_open_call(&env, &creator, &staker);
+
+    client.cancel_call(&call_id);
+    client.cancel_call(&call_id);
+}
+
+#[test]
+#[should_panic]
+fn test_cancel_call_requires_creator_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let creator = Address::generate(&env);
+    let attacker = Address::generate(&env);
+    let (client, _, _, call_id) = setup_open_call(&env, &creator, &attacker);
+
+    env.mock_auths(&[MockAuth {
+        address: &attacker,
+        invoke: &MockAuthInvoke {
+            contract: &client.address,
+            fn_name: "cancel_call",
+            args: (call_id,).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    client.cancel_call(&call_id);
+}
+
+#[test]
+fn test_call_stake_limits() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let creator = Address::generate(&env);
+    let staker = Address::generate(&env);
+    let other_staker = Address::generate(&env);
+    let (client, _, stake_token, call_id) = setup_open_call(&env, &creator, &staker);
+    token::StellarAssetClient::new(&env, &stake_token).mint(&other_staker, &1000);
+
+    let limits = StakeLimits {
+        min_stake: 10,
+        max_user_stake: 300,
+        max_pool: 600,
+    };
+    client.set_call_stake_limits(&call_id, &limits);
+    assert_eq!(client.get_call_stake_limits(&call_id), Some(limits));
+
+    assert!(client
+        .try_stake_on_call(&call_id, &staker, &9, &false)
+        .is_err());
+
+    // Exposure accumulates across both positions
+    client.stake_on_call(&call_id, &staker, &200, &false);
+    assert!(client
+        .try_stake_on_call(&call_id, &staker, &101, &true)
+        .is_err());
+    client.stake_on_call(&call_id, &staker, &100, &true);
+
+    // The pool now holds 400
+    assert!(client
+        .try_stake_on_call(&call_id, &other_staker, &201, &false)
+        .is_err());
+    client.stake_on_call(&call_id, &other_staker, &200, &false);
+
+    // Limits can't change once others have staked
+    assert!(client
+        .try_set_call_stake_limits(&call_id, &StakeLimits::default())
+        .is_err());
+}
+
+#[test]
+fn test_global_stake_limits() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let creator = Address::generate(&env);
+    let staker = Address::generate(&env);
+    let (client, _, _, call_id) = setup_open_call(&env, &creator, &staker);
+
+    assert_eq!(client.get_stake_limits_view(), StakeLimits::default());
+    let limits = StakeLimits {
+        min_stake: 0,
+        max_user_stake: 150,
+        max_pool: 0,
+    };
+    client.set_stake_limits(&limits);
+    assert_eq!(client.get_stake_limits_view(), limits);
+
+    // The creator already holds 100
+    assert!(client
+        .try_stake_on_call(&call_id, &creator, &51, &true)
+        .is_err());
+    client.stake_on_call(&call_id, &creator, &50, &true);
+
+    // A call can't loosen the global limits
+    client.set_call_stake_limits(
+        &call_id,
+        &StakeLimits {
+            min_stake: 0,
+            max_user_stake: 1_000,
+            max_pool: 0,
+        },
+    );
+    assert!(client
+        .try_stake_on_call(&call_id, &staker, &151, &false)
+        .is_err());
+
+    assert!(client
+        .try_set_stake_limits(&StakeLimits {
+            min_stake: 100,
+            max_user_stake: 50,
+            max_pool: 0,
+        })
+        .is_err());
+}
+
+#[test]
+#[should_panic]
+fn test_set_call_stake_limits_requires_creator_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let creator = Address::generate(&env);
+    let attacker = Address::generate(&env);
+    let (client, _, _, call_id) = setup_open_call(&env, &creator, &attacker);
+    let limits = StakeLimits {
+        min_stake: 0,
+        max_user_stake: 100,
+        max_pool: 0,
+    };
+
+    env.mock_auths(&[MockAuth {
+        address: &attacker,
+        invoke: &MockAuthInvoke {
+            contract: &client.address,
+            fn_name: "set_call_stake_limits",
+            args: (call_id, limits.clone()).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    client.set_call_stake_limits(&call_id, &limits);
+}
+
+/// Mark a call settled with a final outcome in the outcome manager
+fn force_settle(env: &Env, outcome_manager_id: &Address, call_id: u64, outcome: bool) {
+    env.as_contract(outcome_manager_id, || {
+        let key = ManagerKey::Call(call_id);
+        let mut call_data: ManagerCallData = env.storage().persistent().get(&key).unwrap();
+        call_data.settled = true;
+        call_data.outcome = Some(outcome);
+        call_data.final_price = Some(105u128);
+        call_data.finalized = true;
+        env.storage().persistent().set(&key, &call_data);
+    });
+}
+
+#[test]
+fn test_claim_pays_winners_and_clears_stakes() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let creator = Address::generate(&env);
+    let staker = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    let (client, outcome_manager_id, stake_token, call_id) =
+        setup_open_call(&env, &creator, &staker);
+    let stake_token_client = token::Client::new(&env, &stake_token);
+    token::StellarAssetClient::new(&env, &stake_token).mint(&opponent, &1000);
+
+    // YES: creator 100 + staker 50, NO: opponent 150
+    client.stake_on_call(&call_id, &staker, &50, &true);
+    client.stake_on_call(&call_id, &opponent, &150, &false);
+    force_settle(&env, &outcome_manager_id, call_id, true);
+
+    assert_eq!(client.claim(&call_id, &staker), 100);
+    let last_event = env.events().all().last().unwrap();
+    let symbol: Symbol = last_event.1.get(0).unwrap().into_val(&env);
+    assert_eq!(symbol, Symbol::new(&env, "PayoutClaimed"));
+    let claimed: i128 = last_event.2.into_val(&env);
+    assert_eq!(claimed, 100);
+
+    assert_eq!(client.claim(&call_id, &creator), 200);
+    assert_eq!(client.claim(&call_id, &opponent), 0);
+
+    assert_eq!(stake_token_client.balance(&staker), 1050);
+    assert_eq!(stake_token_client.balance(&creator), 1100);
+    assert_eq!(stake_token_client.balance(&opponent), 850);
+    assert_eq!(stake_token_client.balance(&outcome_manager_id), 0);
+
+    for user in [&creator, &staker, &opponent] {
+        assert_eq!(client.get_user_stake(&call_id, user, &true), 0);
+        assert_eq!(client.get_user_stake(&call_id, user, &false), 0);
+    }
+
+    // The final outcome is recorded on the registry's call
+    let call = client.get_call(&call_id);
+    assert!(call.settled);
+    assert!(call.outcome);
+    assert_eq!(call.final_price, 105);
+    assert_eq!(call.total_stake_yes, 150);
+    assert_eq!(call.total_stake_no, 150);
+}
+
+#[test]
+fn test_claim_refunds_both_sides_of_voided_call() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let creator = Address::generate(&env);
+    let staker = Address::generate(&env);
+    let (client, outcome_manager_id, stake_token, call_id) =
+        setup_open_call(&env, &creator, &staker);
+    let stake_token_client = token::Client::new(&env, &stake_token);
+    let outcome_manager = OutcomeManagerContractClient::new(&env, &outcome_manager_id);
+
+    client.stake_on_call(&call_id, &staker, &30, &true);
+    client.stake_on_call(&call_id, &staker, &20, &false);
+    outcome_manager.void_call(&outcome_manager.get_owner(), &call_id);
+
+    assert_eq!(client.claim(&call_id, &staker), 50);
+    assert_eq!(stake_token_client.balance(&staker), 1000);
+    assert!(outcome_manager.has_withdrawn(&call_id, &staker));
+    assert!(!client.get_call(&call_id).settled);
+}
+
+#[test]
+#[should_panic(expected = "Nothing to claim")]
+fn test_claim_twice() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let creator = Address::generate(&env);
+    let staker = Address::generate(&env);
+    let (client, outcome_manager_id, _, call_id) = setup_open_call(&env, &creator, &staker);
+
+    client.stake_on_call(&call_id, &staker, &50, &false);
+    force_settle(&env, &outcome_manager_id, call_id, false);
+
+    client.claim(&call_id, &staker);
+    client.claim(&call_id, &staker);
+}
+
+#[test]
+#[should_panic(expected = "Call not settled")]
+fn test_claim_before_settlement() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let creator = Address::generate(&env);
+    let staker = Address::generate(&env);
+    let (client, _, _, call_id) = setup_open_call(&env, &creator, &staker);
+
+    client.stake_on_call(&call_id, &staker, &50, &false);
+    client.claim(&call_id, &staker);
+}
+
+#[test]
+#[should_panic]
+fn test_claim_requires_user_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let creator = Address::generate(&env);
+    let attacker = Address::generate(&env);
+    let (client, outcome_manager_id, _, call_id) = setup_open_call(&env, &creator, &attacker);
+
+    client.stake_on_call(&call_id, &attacker, &50, &false);
+    force_settle(&env, &outcome_manager_id, call_id, true);
+
+    env.mock_auths(&[MockAuth {
+        address: &attacker,
+        invoke: &MockAuthInvoke {
+            contract: &client.address,
+            fn_name: "claim",
+            args: (call_id, creator.clone()).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    client.claim(&call_id, &creator);
+}
+
+#[test]
+fn test_call_entries_ttl_extended() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let creator = Address::generate(&env);
+    let staker = Address::generate(&env);
+    let (client, outcome_manager_id, _, call_id) = setup_open_call(&env, &creator, &staker);
+    client.stake_on_call(&call_id, &staker, &50, &false);
+
+    let call_key = DataKey::Call(call_id);
+    let stake_key = DataKey::UserStake(call_id, staker.clone(), false);
+    env.as_contract(&client.address, || {
+        let storage = env.storage().persistent();
+        assert_eq!(storage.get_ttl(&call_key), DEFAULT_TTL_EXTEND_TO);
+        assert_eq!(storage.get_ttl(&stake_key), DEFAULT_TTL_EXTEND_TO);
+        assert_eq!(storage.get_ttl(&DataKey::Admin), DEFAULT_TTL_EXTEND_TO);
+        assert_eq!(env.storage().instance().get_ttl(), DEFAULT_TTL_EXTEND_TO);
+    });
+
+    // Close to the threshold, anyone can bump the call back to the full TTL
+    let elapsed = DEFAULT_TTL_EXTEND_TO - DEFAULT_TTL_THRESHOLD + 1;
+    env.ledger().with_mut(|li| li.sequence_number += elapsed);
+    client.bump_call(&call_id);
+
+    env.as_contract(&client.address, || {
+        let storage = env.storage().persistent();
+        assert_eq!(storage.get_ttl(&call_key), DEFAULT_TTL_EXTEND_TO);
+        assert_eq!(storage.get_ttl(&DataKey::Admin), DEFAULT_TTL_EXTEND_TO);
+        // Stakes are extended whenever they are read or written
+        assert_eq!(storage.get_ttl(&stake_key), DEFAULT_TTL_EXTEND_TO - elapsed);
+    });
+    assert_eq!(client.get_user_stake(&call_id, &staker, &false), 50);
+    env.as_contract(&client.address, || {
+        assert_eq!(
+            env.storage().persistent().get_ttl(&stake_key),
+            DEFAULT_TTL_EXTEND_TO
+        );
+    });
+
+    env.as_contract(&outcome_manager_id, || {
+        let key = outcome_manager::DataKey::Call(call_id);
+        assert_eq!(
+            env.storage().persistent().get_ttl(&key),
+            DEFAULT_TTL_EXTEND_TO
+        );
+    });
+}
+
+#[test]
+fn test_bump_stake() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let creator = Address::generate(&env);
+    let staker = Address::generate(&env);
+    let (client, _, _, call_id) = setup_open_call(&env, &creator, &staker);
+    client.stake_on_call(&call_id, &staker, &50, &false);
+    client.stake_on_call(&call_id, &staker, &20, &true);
+
+    let no_key = DataKey::UserStake(call_id, staker.clone(), false);
+    let yes_key = DataKey::UserStake(call_id, staker.clone(), true);
+    let elapsed = DEFAULT_TTL_EXTEND_TO - DEFAULT_TTL_THRESHOLD + 1;
+    env.ledger().with_mut(|li| li.sequence_number += elapsed);
+
+    // bump_call leaves other stakers' entries alone
+    client.bump_call(&call_id);
+    env.as_contract(&client.address, || {
+        let storage = env.storage().persistent();
+        assert_eq!(storage.get_ttl(&no_key), DEFAULT_TTL_EXTEND_TO - elapsed);
+        assert_eq!(storage.get_ttl(&yes_key), DEFAULT_TTL_EXTEND_TO - elapsed);
+    });
+
+    client.bump_stake(&call_id, &staker);
+    env.as_contract(&client.address, || {
+        let storage = env.storage().persistent();
+        assert_eq!(storage.get_ttl(&no_key), DEFAULT_TTL_EXTEND_TO);
+        assert_eq!(storage.get_ttl(&yes_key), DEFAULT_TTL_EXTEND_TO);
+    });
+
+    assert!(client
+        .try_bump_stake(&call_id, &Address::generate(&env))
+        .is_err());
+}
+
+#[test]
+fn test_set_ttl_config() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let creator = Address::generate(&env);
+    let staker = Address::generate(&env);
+    let (client, _, _, _) = setup_open_call(&env, &creator, &staker);
+
+    client.set_ttl_config(&5_000, &10_000);
+    assert_eq!(
+        client.get_ttl_config_view(),
+        TtlConfig {
+            threshold: 5_000,
+            extend_to: 10_000,
+        }
+    );
+
+    client.stake_on_call(&0, &staker, &50, &false);
+    env.as_contract(&client.address, || {
+        let key = DataKey::UserStake(0, staker.clone(), false);
+        assert_eq!(env.storage().persistent().get_ttl(&key), 10_000);
+    });
+}
+
+#[test]
+#[should_panic(expected = "Threshold must be > 0 and below extend_to")]
+fn test_set_ttl_config_invalid() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, CallRegistry);
+    let client = CallRegistryClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    client.set_ttl_config(&10_000, &10_000);
+}
+
+#[test]
+#[should_panic]
+fn test_set_ttl_config_requires_admin_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, CallRegistry);
+    let client = CallRegistryClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    let attacker = Address::generate(&env);
+    client.initialize(&admin);
+
+    env.mock_auths(&[MockAuth {
+        address: &attacker,
+        invoke: &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "set_ttl_config",
+            args: (5_000u32, 10_000u32).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    client.set_ttl_config(&5_000, &10_000);
+}
+
+/// Smallest module the host accepts as contract code: a wasm header and the
+/// env meta section declaring the interface version
+fn minimal_wasm(env: &Env) -> Bytes {
+    let name = b"contractenvmetav0";
+    let mut meta = [0u8; 12];
+    meta[4..].copy_from_slice(&(21u64 << 32).to_be_bytes());
+
+    let mut wasm = Bytes::from_array(env, &[0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00]);
+    wasm.push_back(0);
+    wasm.push_back((1 + name.len() + meta.len()) as u8);
+    wasm.push_back(name.len() as u8);
+    wasm.extend_from_slice(name);
+    wasm.extend_from_slice(&meta);
+    wasm
+}
+
+#[test]
+fn test_migrate_v1_calls_in_batches() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let creator = Address::generate(&env);
+    let staker = Address::generate(&env);
+    let (client, _, stake_token, _) = setup_open_call(&env, &creator, &staker);
+
+    // Rewrite storage the way a version 1 deployment left it
+    env.as_contract(&client.address, || {
+        for call_id in 0..5u64 {
+            let legacy = CallV1 {
+                creator: creator.clone(),
+                stake_token: stake_token.clone(),
+                total_stake_yes: 100,
+                total_stake_no: 0,
+                start_ts: 0,
+                end_ts: 1000,
+                token_address: stake_token.clone(),
+                pair_id: BytesN::from_array(&env, &[0; 32]),
+                ipfs_cid: String::from_str(&env, "QmHash"),
+                settled: false,
+                outcome: false,
+                final_price: 0,
+            };
+            env.storage()
+                .persistent()
+                .set(&DataKey::Call(call_id), &legacy);
+        }
+        env.storage().instance().set(&DataKey::NextCallId, &5u64);
+        env.storage().instance().remove(&DataKey::SchemaVersion);
+    });
+    // Version 1 held every stake in the registry
+    let stake_token_client = token::Client::new(&env, &stake_token);
+    token::StellarAssetClient::new(&env, &stake_token).mint(&client.address, &500);
+    assert_eq!(client.get_schema_version(), 1);
+    assert!(client.try_get_call(&4).is_err());
+
+    assert_eq!(client.migrate(&2), 1);
+    assert!(!client.get_call(&1).cancelled);
+    assert!(client.try_get_call(&4).is_err());
+
+    assert_eq!(client.migrate(&2), 1);
+    assert_eq!(client.migrate(&2), CURRENT_SCHEMA_VERSION);
+    assert_eq!(client.get_schema_version(), CURRENT_SCHEMA_VERSION);
+    assert_eq!(client.get_call(&4).total_stake_yes, 100);
+    assert!(client.try_migrate(&2).is_err());
+    assert_eq!(stake_token_client.balance(&client.address), 0);
+}
+
+#[test]
+fn test_legacy_call_pays_out_after_migration() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, CallRegistry);
+    let client = CallRegistryClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    let outcome_manager_id = register_outcome_manager(&env, &client, &admin);
+    let outcome_manager = OutcomeManagerContractClient::new(&env, &outcome_manager_id);
+
+    let creator = Address::generate(&env);
+    let staker = Address::generate(&env);
+    let stake_token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let stake_token_client = token::Client::new(&env, &stake_token);
+
+    // Version 1 left call 0 known to both contracts, with the outcome
+    // manager's record migrated without escrow and with totals that disagree
+    // with the registry's, and call 1 only known to the registry; the
+    // registry held all 180 staked
+    token::StellarAssetClient::new(&env, &stake_token).mint(&contract_id, &180);
+    env.as_contract(&contract_id, || {
+        let storage = env.storage().persistent();
+        for (call_id, total_stake_yes, total_stake_no) in [(0u64, 100i128, 50i128), (1, 30, 0)] {
+            let legacy = CallV1 {
+                creator: creator.clone(),
+                stake_token: stake_token.clone(),
+                total_stake_yes,
+                total_stake_no,
+                start_ts: 0,
+                end_ts: 1000,
+                token_address: stake_token.clone(),
+                pair_id: BytesN::from_array(&env, &[0; 32]),
+                ipfs_cid: String::from_str(&env, "QmHash"),
+                settled: false,
+                outcome: false,
+                final_price: 0,
+            };
+            storage.set(&DataKey::Call(call_id), &legacy);
+        }
+        storage.set(&DataKey::UserStake(0, creator.clone(), true), &100i128);
+        storage.set(&DataKey::UserStake(0, staker.clone(), false), &50i128);
+        storage.set(&DataKey::UserStake(1, creator.clone(), true), &30i128);
+        env.storage().instance().set(&DataKey::NextCallId, &2u64);
+        env.storage().instance().remove(&DataKey::SchemaVersion);
+    });
+    env.as_contract(&outcome_manager_id, || {
+        let call_data = ManagerCallData {
+            id: 0,
+            token: stake_token.clone(),
+            long_tokens: 100,
+            short_tokens: 20,
+            end_ts: 1000,
+            settled: false,
+            outcome: None,
+            final_price: None,
+            escrow: 0,
+            dispute_deadline: 0,
+            finalized: false,
+            voided: false,
+            cancelled: false,
+        };
+        env.storage()
+            .persistent()
+            .set(&ManagerKey::Call(0), &call_data);
+    });
+
+    assert_eq!(client.migrate(&10), CURRENT_SCHEMA_VERSION);
+    assert_eq!(stake_token_client.balance(&contract_id), 0);
+    assert_eq!(stake_token_client.balance(&outcome_manager_id), 180);
+    let funded = outcome_manager.get_call(&0).unwrap();
+    assert_eq!(funded.escrow, 150);
+    assert_eq!(funded.long_tokens, 100);
+    assert_eq!(funded.short_tokens, 50);
+    let registered = outcome_manager.get_call(&1).unwrap();
+    assert_eq!(registered.escrow, 30);
+    assert_eq!(registered.long_tokens, 30);
+
+    force_settle(&env, &outcome_manager_id, 0, true);
+    assert_eq!(client.claim(&0, &creator), 150);
+    assert_eq!(client.claim(&0, &staker), 0);
+    assert_eq!(stake_token_client.balance(&creator), 150);
+    assert_eq!(outcome_manager.get_call(&0).unwrap().escrow, 0);
+}
+
+#[test]
+fn test_calls_blocked_until_migrated() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let creator = Address::generate(&env);
+    let staker = Address::generate(&env);
+    let (client, _, stake_token, call_id) = setup_open_call(&env, &creator, &staker);
+
+    // A version 1 call left behind by the previous code
+    env.as_contract(&client.address, || {
+        let legacy = CallV1 {
+            creator: creator.clone(),
+            stake_token: stake_token.clone(),
+            total_stake_yes: 100,
+            total_stake_no: 0,
+            start_ts: 0,
+            end_ts: 1000,
+            token_address: stake_token.clone(),
+            pair_id: BytesN::from_array(&env, &[0; 32]),
+            ipfs_cid: String::from_str(&env, "QmHash"),
+            settled: false,
+            outcome: false,
+            final_price: 0,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::Call(call_id), &legacy);
+        env.storage().instance().remove(&DataKey::SchemaVersion);
+    });
+    token::StellarAssetClient::new(&env, &stake_token).mint(&client.address, &100);
+
+    let end_ts = env.ledger().timestamp() + 1000;
+    let metadata = CreateCallMetadata {
+        token_address: Address::generate(&env),
+        pair_id: BytesN::from_array(&env, &[0; 32]),
+        ipfs_cid: String::from_str(&env, "QmHash"),
+    };
+    assert!(client
+        .try_create_call(&creator, &stake_token, &100, &end_ts, &metadata, &0)
+        .is_err());
+    assert!(client
+        .try_stake_on_call(&call_id, &staker, &50, &false)
+        .is_err());
+
+    // Nothing new landed past the cursor, so the batch decodes cleanly
+    assert_eq!(client.migrate(&10), CURRENT_SCHEMA_VERSION);
+    let new_call = client.create_call(&creator, &stake_token, &100, &end_ts, &metadata, &0);
+    assert_eq!(new_call, call_id + 1);
+    client.stake_on_call(&call_id, &staker, &50, &false);
+}
+
+#[test]
+#[should_panic(expected = "Schema already current")]
+fn test_migrate_current_schema() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, CallRegistry);
+    let client = CallRegistryClient::new(&env, &contract_id);
+    client.initialize(&Address::generate(&env));
+
+    client.migrate(&10);
+}
+
+#[test]
+fn test_upgrade() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, CallRegistry);
+    let client = CallRegistryClient::new(&env, &contract_id);
+    client.initialize(&Address::generate(&env));
+
+    let wasm_hash = env.deployer().upload_contract_wasm(minimal_wasm(&env));
+    client.upgrade(&wasm_hash);
+
+    let last_event = env.events().all().last().unwrap();
+    let symbol: Symbol = last_event.1.get(0).unwrap().into_val(&env);
+    assert_eq!(symbol, Symbol::new(&env, "ContractUpgraded"));
+    let hash: BytesN<32> = last_event.2.into_val(&env);
+    assert_eq!(hash, wasm_hash);
+}
+
+#[test]
+#[should_panic]
+fn test_upgrade_requires_admin_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, CallRegistry);
+    let client = CallRegistryClient::new(&env, &contract_id);
+    let attacker = Address::generate(&env);
+    client.initialize(&Address::generate(&env));
+    let wasm_hash = env.deployer().upload_contract_wasm(minimal_wasm(&env));
+
+    env.mock_auths(&[MockAuth {
+        address: &attacker,
+        invoke: &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "upgrade",
+            args: (wasm_hash.clone(),).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    client.upgrade(&wasm_hash);
+}
+
+#[test]
+fn test_two_step_admin_transfer() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, CallRegistry);
+    let client = CallRegistryClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    client.propose_admin(&new_admin);
+    assert_eq!(client.get_pending_admin(), Some(new_admin.clone()));
+    assert_eq!(client.get_admin_view(), admin);
+
+    client.accept_admin();
+    assert_eq!(env.auths()[0].0, new_admin);
+    assert_eq!(client.get_admin_view(), new_admin);
+    assert_eq!(client.get_pending_admin(), None);
+
+    let last_event = env.events().all().last().unwrap();
+    let symbol: Symbol = last_event.1.get(0).unwrap().into_val(&env);
+    assert_eq!(symbol, Symbol::new(&env, "AdminTransferred"));
+
+    // The new admin now holds admin rights
+    client.pause(&new_admin);
+    assert_eq!(env.auths()[0].0, new_admin);
+    assert!(client.try_unpause(&admin).is_err());
+}
+
+#[test]
+#[should_panic(expected = "No pending admin")]
+fn test_cancel_admin_proposal() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, CallRegistry);
+    let client = CallRegistryClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    client.propose_admin(&Address::generate(&env));
+    client.cancel_admin_proposal();
+    assert_eq!(client.get_pending_admin(), None);
+
+    client.accept_admin();
+}
+
+#[test]
+#[should_panic]
+fn test_accept_admin_requires_proposed_admin_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, CallRegistry);
+    let client = CallRegistryClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+    client.initialize(&admin);
+    client.propose_admin(&new_admin);
+
+    env.mock_auths(&[MockAuth {
+        address: &admin,
+        invoke: &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "accept_admin",
+            args: ().into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    client.accept_admin();
+}
+
+#[test]
+#[should_panic]
+fn test_propose_admin_requires_admin_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, CallRegistry);
+    let client = CallRegistryClient::new(&env, &contract_id);
+    let attacker = Address::generate(&env);
+    client.initialize(&Address::generate(&env));
+
+    env.mock_auths(&[MockAuth {
+        address: &attacker,
+        invoke: &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "propose_admin",
+            args: (attacker.clone(),).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    client.propose_admin(&attacker);
+}
+
+#[test]
+fn test_pauser_role() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, CallRegistry);
+    let client = CallRegistryClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    let pauser = Address::generate(&env);
+    client.initialize(&admin);
+
+    assert!(client.has_role(&Role::Pauser, &admin));
+    assert!(!client.has_role(&Role::Pauser, &pauser));
+    assert!(client.try_pause(&pauser).is_err());
+
+    client.grant_role(&Role::Pauser, &pauser);
+    assert!(client.has_role(&Role::Pauser, &pauser));
+    let last_event = env.events().all().last().unwrap();
+    let symbol: Symbol = last_event.1.get(0).unwrap().into_val(&env);
+    assert_eq!(symbol, Symbol::new(&env, "RoleGranted"));
+
+    client.pause(&pauser);
+    assert_eq!(env.auths()[0].0, pauser);
+    assert!(client.get_is_paused());
+    client.unpause(&pauser);
+    assert!(!client.get_is_paused());
+
+    client.revoke_role(&Role::Pauser, &pauser);
+    assert!(!client.has_role(&Role::Pauser, &pauser));
+    assert!(client.try_pause(&pauser).is_err());
+}
+
+#[test]
+#[should_panic]
+fn test_grant_role_requires_admin_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, CallRegistry);
+    let client = CallRegistryClient::new(&env, &contract_id);
+    let attacker = Address::generate(&env);
+    client.initialize(&Address::generate(&env));
+
+    env.mock_auths(&[MockAuth {
+        address: &attacker,
+        invoke: &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "grant_role",
+            args: (Role::Pauser, attacker.clone()).into_val(&env),
             sub_invokes: &[],
         },
     }]);
-    client.unpause();
+    client.grant_role(&Role::Pauser, &attacker);
 }
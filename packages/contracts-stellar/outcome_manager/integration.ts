@@ -4,6 +4,16 @@
  */
 
 import * as libsodium from 'libsodium.js';
+import { Address, hash } from '@stellar/stellar-sdk';
+
+/**
+ * Version byte and domain tag prefixed to every outcome message.
+ * Must match OUTCOME_MESSAGE_VERSION / OUTCOME_MESSAGE_DOMAIN in the contract.
+ */
+export const OUTCOME_MESSAGE_VERSION = 1;
+export const OUTCOME_MESSAGE_DOMAIN = 'BACKIT_OUTCOME';
+/** Outcome byte signed to void a call (0 = NO, 1 = YES) */
+export const VOID_OUTCOME = 2;
 
 export interface OutcomeData {
   callId: bigint;
@@ -14,6 +24,14 @@ export interface OutcomeData {
   signature: Uint8Array;
 }
 
+/**
+ * Deployment the outcome is signed for
+ */
+export interface OutcomeMessageContext {
+  networkPassphrase: string;
+  contractAddress: string;
+}
+
 export interface OracleKeypair {
   publicKey: Uint8Array;
   privateKey: Uint8Array;
@@ -34,34 +52,79 @@ export async function generateOracleKeypair(
 
 /**
  * Build the message that needs to be signed for outcome submission
- * Format: [8 bytes: call_id] [1 byte: outcome] [16 bytes: final_price] [8 bytes: timestamp]
+ * Format: [1 byte: version] [domain tag] [32 bytes: sha256(network passphrase)]
+ *         [contract address as ScVal XDR] [8 bytes: call_id] [1 byte: outcome]
+ *         [16 bytes: final_price] [8 bytes: timestamp]
+ * Mirrors the contract's `outcome_message` view.
+ */
+export function buildOutcomeMessage(
+  data: OutcomeData,
+  context: OutcomeMessageContext
+): Uint8Array {
+  return buildMessage(
+    data.callId,
+    data.outcome ? 1 : 0,
+    data.finalPrice,
+    data.timestamp,
+    context
+  );
+}
+
+/**
+ * Build the message that needs to be signed to void a call
+ * Same format as the outcome message with outcome byte 2 and a zero price.
+ * Mirrors the contract's `void_message` view.
  */
-export function buildOutcomeMessage(data: OutcomeData): Uint8Array {
-  const buffer = new Uint8Array(33); // 8 + 1 + 16 + 8 = 33 bytes
+export function buildVoidMessage(
+  callId: bigint,
+  timestamp: bigint,
+  context: OutcomeMessageContext
+): Uint8Array {
+  return buildMessage(callId, VOID_OUTCOME, 0n, timestamp, context);
+}
+
+function buildMessage(
+  callId: bigint,
+  outcomeByte: number,
+  finalPrice: bigint,
+  timestamp: bigint,
+  context: OutcomeMessageContext
+): Uint8Array {
+  const domain = new TextEncoder().encode(OUTCOME_MESSAGE_DOMAIN);
+  const networkId = hash(Buffer.from(context.networkPassphrase));
+  const contract = Address.fromString(context.contractAddress).toScVal().toXDR();
+
+  const payload = new Uint8Array(33); // 8 + 1 + 16 + 8 = 33 bytes
 
   // Write call_id (u64, big-endian)
-  const callIdView = new DataView(buffer.buffer, 0, 8);
-  callIdView.setBigInt64(0, BigInt(data.callId), false); // false = big-endian
+  const callIdView = new DataView(payload.buffer, 0, 8);
+  callIdView.setBigUint64(0, BigInt(callId), false); // false = big-endian
 
   // Write outcome (u8)
-  buffer[8] = data.outcome ? 1 : 0;
+  payload[8] = outcomeByte;
 
   // Write final_price (u128, big-endian, 16 bytes)
   // Split u128 into two u64 for proper encoding
-  const priceUpperView = new DataView(buffer.buffer, 9, 8);
-  const priceLowerView = new DataView(buffer.buffer, 17, 8);
+  const priceUpperView = new DataView(payload.buffer, 9, 8);
+  const priceLowerView = new DataView(payload.buffer, 17, 8);
 
-  const upper = data.finalPrice >> 64n;
-  const lower = data.finalPrice & ((1n << 64n) - 1n);
+  const upper = finalPrice >> 64n;
+  const lower = finalPrice & ((1n << 64n) - 1n);
 
-  priceUpperView.setBigInt64(0, BigInt(upper), false);
-  priceLowerView.setBigInt64(0, BigInt(lower), false);
+  priceUpperView.setBigUint64(0, BigInt(upper), false);
+  priceLowerView.setBigUint64(0, BigInt(lower), false);
 
   // Write timestamp (u64, big-endian)
-  const timestampView = new DataView(buffer.buffer, 25, 8);
-  timestampView.setBigInt64(0, BigInt(data.timestamp), false);
-
-  return buffer;
+  const timestampView = new DataView(payload.buffer, 25, 8);
+  timestampView.setBigUint64(0, BigInt(timestamp), false);
+
+  return Buffer.concat([
+    Buffer.from([OUTCOME_MESSAGE_VERSION]),
+    Buffer.from(domain),
+    networkId,
+    contract,
+    Buffer.from(payload),
+  ]);
 }
 
 /**
@@ -100,10 +163,12 @@ export async function verifyOutcomeSignature(
 export class OracleSigner {
   private privateKey: Uint8Array;
   private publicKey: Uint8Array;
+  private context: OutcomeMessageContext;
 
-  constructor(keypair: OracleKeypair) {
+  constructor(keypair: OracleKeypair, context: OutcomeMessageContext) {
     this.privateKey = keypair.privateKey;
     this.publicKey = keypair.publicKey;
+    this.context = context;
   }
 
   /**
@@ -117,7 +182,7 @@ export class OracleSigner {
    * Create a complete outcome signature
    */
   async signOutcome(data: OutcomeData): Promise<OutcomeData> {
-    const message = buildOutcomeMessage(data);
+    const message = buildOutcomeMessage(data, this.context);
     const signature = await signOutcome(message, this.privateKey);
 
     return {
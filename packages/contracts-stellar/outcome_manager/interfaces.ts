@@ -3,6 +3,11 @@
  * Use these types for off-chain interactions
  */
 
+import { Address, hash } from '@stellar/stellar-sdk';
+
+export const OUTCOME_MESSAGE_VERSION = 1;
+export const OUTCOME_MESSAGE_DOMAIN = 'BACKIT_OUTCOME';
+
 export interface CallData {
   id: bigint;
   token: string;
@@ -12,6 +17,42 @@ export interface CallData {
   settled: boolean;
   outcome: boolean | null;
   final_price: bigint | null;
+  escrow: bigint;
+  dispute_deadline: bigint;
+  finalized: boolean;
+  voided: boolean;
+  cancelled: boolean;
+}
+
+export type CallStatus =
+  | 'Open'
+  | 'Unopposed'
+  | 'AwaitingOutcome'
+  | 'Provisional'
+  | 'Disputed'
+  | 'Final'
+  | 'Voided'
+  | 'Cancelled';
+
+/** Per-call result of `claim_many`; `Paid` is net of fees */
+export type ClaimResult =
+  | { Paid: bigint }
+  | 'AlreadyClaimed'
+  | 'NotSettled';
+
+export interface DisputeConfig {
+  period: bigint;
+  bond: bigint;
+}
+
+export interface TtlConfig {
+  threshold: number;
+  extend_to: number;
+}
+
+export interface Dispute {
+  challenger: string;
+  bond: bigint;
 }
 
 export interface OutcomeSubmittedEvent {
@@ -21,6 +62,106 @@ export interface OutcomeSubmittedEvent {
   oracle: string;
 }
 
+export interface QuorumOutcomeSubmittedEvent {
+  call_id: bigint;
+  outcome: boolean;
+  final_price: bigint;
+  oracles: string[];
+}
+
+export interface OutcomeChallengedEvent {
+  call_id: bigint;
+  challenger: string;
+  bond: bigint;
+}
+
+export interface OutcomeFinalizedEvent {
+  call_id: bigint;
+  outcome: boolean;
+  final_price: bigint;
+}
+
+export interface DisputeResolvedEvent {
+  call_id: bigint;
+  outcome: boolean;
+  final_price: bigint;
+  upheld: boolean;
+}
+
+export interface CallVoidedEvent {
+  call_id: bigint;
+}
+
+export interface StakeRefundedEvent {
+  call_id: bigint;
+  user: string;
+  amount: bigint;
+}
+
+export interface UnopposedRefundedEvent {
+  call_id: bigint;
+  user: string;
+  amount: bigint;
+}
+
+export interface OwnerProposedEvent {
+  owner: string;
+  new_owner: string;
+}
+
+export interface OwnershipTransferredEvent {
+  old_owner: string;
+  new_owner: string;
+}
+
+export interface OwnerProposalCancelledEvent {
+  pending_owner: string;
+}
+
+export type Role =
+  | 'Pauser'
+  | 'FeeManager'
+  | 'OracleManager'
+  | 'SettlementArbiter';
+
+export interface RoleGrantedEvent {
+  role: Role;
+  account: string;
+}
+
+export interface RoleRevokedEvent {
+  role: Role;
+  account: string;
+}
+
+export interface CreatorFee {
+  creator: string;
+  basis_points: number;
+}
+
+export interface CreatorRewardedEvent {
+  call_id: bigint;
+  creator: string;
+  amount: bigint;
+}
+
+export interface FeesWithdrawnEvent {
+  token: string;
+  to: string;
+  amount: bigint;
+}
+
+export interface PayoutTotals {
+  paid: bigint;
+  claimed_stake: bigint;
+}
+
+export interface DustSweptEvent {
+  call_id: bigint;
+  treasury: string;
+  amount: bigint;
+}
+
 export interface PayoutWithdrawnEvent {
   call_id: bigint;
   user: string;
@@ -34,8 +175,23 @@ export interface OracleUpdatedEvent {
 
 export type OutcomeManagerEvent =
   | { OutcomeSubmitted: OutcomeSubmittedEvent }
+  | { QuorumOutcomeSubmitted: QuorumOutcomeSubmittedEvent }
+  | { OutcomeChallenged: OutcomeChallengedEvent }
+  | { OutcomeFinalized: OutcomeFinalizedEvent }
+  | { DisputeResolved: DisputeResolvedEvent }
+  | { CallVoided: CallVoidedEvent }
+  | { StakeRefunded: StakeRefundedEvent }
+  | { UnopposedRefunded: UnopposedRefundedEvent }
   | { PayoutWithdrawn: PayoutWithdrawnEvent }
-  | { OracleUpdated: OracleUpdatedEvent };
+  | { OracleUpdated: OracleUpdatedEvent }
+  | { OwnerProposed: OwnerProposedEvent }
+  | { OwnershipTransferred: OwnershipTransferredEvent }
+  | { OwnerProposalCancelled: OwnerProposalCancelledEvent }
+  | { RoleGranted: RoleGrantedEvent }
+  | { RoleRevoked: RoleRevokedEvent }
+  | { CreatorRewarded: CreatorRewardedEvent }
+  | { FeesWithdrawn: FeesWithdrawnEvent }
+  | { DustSwept: DustSweptEvent };
 
 export interface SignatureMessage {
   call_id: bigint;
@@ -53,16 +209,18 @@ export class OutcomeManagerClient {
 
   /**
    * Build the message bytes for oracle signature
-   * Format: [8 bytes: call_id] [1 byte: outcome] [16 bytes: final_price] [8 bytes: timestamp]
+   * Format: [1 byte: version] [domain tag] [32 bytes: sha256(network passphrase)]
+   *         [contract address as ScVal XDR] [8 bytes: call_id] [1 byte: outcome]
+   *         [16 bytes: final_price] [8 bytes: timestamp]
    */
-  buildSignatureMessage(message: SignatureMessage): Buffer {
-    const buffer = Buffer.alloc(33); // 8 + 1 + 16 + 8 = 33 bytes
+  buildSignatureMessage(message: SignatureMessage, networkPassphrase: string): Buffer {
+    const payload = Buffer.alloc(33); // 8 + 1 + 16 + 8 = 33 bytes
 
     // Write call_id (u64, big-endian)
-    buffer.writeBigUInt64BE(message.call_id, 0);
+    payload.writeBigUInt64BE(message.call_id, 0);
 
     // Write outcome (u8)
-    buffer.writeUInt8(message.outcome ? 1 : 0, 8);
+    payload.writeUInt8(message.outcome ? 1 : 0, 8);
 
     // Write final_price (u128, big-endian, 16 bytes)
     // Split u128 into two u64 for JS compatibility
@@ -70,13 +228,19 @@ export class OutcomeManagerClient {
     const upper = priceBigInt >> 64n;
     const lower = priceBigInt & ((1n << 64n) - 1n);
 
-    buffer.writeBigUInt64BE(upper, 9);
-    buffer.writeBigUInt64BE(lower, 17);
+    payload.writeBigUInt64BE(upper, 9);
+    payload.writeBigUInt64BE(lower, 17);
 
     // Write timestamp (u64, big-endian)
-    buffer.writeBigUInt64BE(message.timestamp, 25);
+    payload.writeBigUInt64BE(message.timestamp, 25);
 
-    return buffer;
+    return Buffer.concat([
+      Buffer.from([OUTCOME_MESSAGE_VERSION]),
+      Buffer.from(OUTCOME_MESSAGE_DOMAIN),
+      hash(Buffer.from(networkPassphrase)),
+      Address.fromString(this.contractAddress).toScVal().toXDR(),
+      payload,
+    ]);
   }
 
   /**
@@ -97,6 +261,11 @@ export function calculatePayout(
   longTokens: bigint,
   shortTokens: bigint
 ): bigint {
+  if (longTokens === 0n || shortTokens === 0n) {
+    // Unopposed call - stake is refunded without a fee
+    return userStake;
+  }
+
   if (userSide !== outcome) {
     // User lost
     return 0n;
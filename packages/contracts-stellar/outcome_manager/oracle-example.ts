@@ -30,10 +30,11 @@ export class OracleService {
 
   constructor(
     contractAddress: string,
+    networkPassphrase: string,
     keypair: Awaited<ReturnType<typeof generateOracleKeypair>>
   ) {
     this.contractAddress = contractAddress;
-    this.signer = new OracleSigner(keypair);
+    this.signer = new OracleSigner(keypair, { networkPassphrase, contractAddress });
     this.auditTrail = new AuditTrail();
   }
 
@@ -177,7 +178,8 @@ export async function exampleOracleSetup() {
 
   // Step 2: Initialize oracle service
   const contractAddress = 'CAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAB5L';
-  const oracle = new OracleService(contractAddress, keypair);
+  const networkPassphrase = 'Test SDF Network ; September 2015';
+  const oracle = new OracleService(contractAddress, networkPassphrase, keypair);
   console.log('Oracle initialized for contract:', oracle.getContractAddress());
 
   // Step 3: Example - Settle a call
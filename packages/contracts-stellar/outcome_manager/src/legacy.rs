@@ -0,0 +1,24 @@
+use soroban_sdk::{contracttype, Address};
+
+/// `CallData` layout stored by schema version 1, kept in the instance
+/// `CALLS` map before per-key storage
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct CallDataV1 {
+    pub id: u64,
+    pub token: Address,
+    pub long_tokens: u128,
+    pub short_tokens: u128,
+    pub end_ts: u64,
+    pub settled: bool,
+    pub outcome: Option<bool>,
+    pub final_price: Option<u128>,
+}
+
+/// `FeeConfig` layout stored by schema version 1, before fee modes
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeeConfigV1 {
+    pub basis_points: u32,
+    pub treasury: Address,
+}
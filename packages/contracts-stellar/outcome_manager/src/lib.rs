@@ -1,19 +1,101 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contractimpl, contracttype, symbol_short, token, Address, Bytes, BytesN, Env, Map,
-    Symbol,
+    contract, contractimpl, contracttype, symbol_short, token, vec, xdr::ToXdr, Address, Bytes,
+    BytesN, Env, Map, Symbol, Vec,
 };
 
+pub mod legacy;
+pub mod registry;
+
+use legacy::{CallDataV1, FeeConfigV1};
+use registry::CallRegistryClient;
+
 const OWNER: Symbol = symbol_short!("OWNER");
+const PENDING_OWNER: Symbol = symbol_short!("PEND_OWN");
 const ORACLES: Symbol = symbol_short!("ORACLES");
-const CALLS: Symbol = symbol_short!("CALLS");
-const WITHDRAWALS: Symbol = symbol_short!("WITHDRAW");
 const CALL_REGISTRY: Symbol = symbol_short!("CALL_REG");
 const IS_PAUSED: Symbol = symbol_short!("PAUSED");
 const FEE_CONFIG: Symbol = symbol_short!("FEE_CFG");
+const MAX_FEE: Symbol = symbol_short!("MAX_FEE");
+const SETTLEMENT_WINDOW: Symbol = symbol_short!("SETL_WIN");
+const ORACLE_THRESHOLD: Symbol = symbol_short!("ORC_THR");
+const DISPUTE_CONFIG: Symbol = symbol_short!("DSP_CFG");
+const TTL_CONFIG: Symbol = symbol_short!("TTL_CFG");
+const SCHEMA_VERSION: Symbol = symbol_short!("SCHEMA");
+
+/// Schema version 1 instance-storage maps holding every call and withdrawal
+/// flag; `migrate` moves their entries to `DataKey`s
+const CALLS: Symbol = symbol_short!("CALLS");
+const WITHDRAWALS: Symbol = symbol_short!("WITHDRAW");
+
+/// Layout version of stored data written by this code
+/// 1: initial release, calls and withdrawals in instance maps
+/// 2: per-key calls, withdrawals and disputes with the current `CallData`,
+///    and `FeeConfig::mode`
+pub const CURRENT_SCHEMA_VERSION: u32 = 2;
 
 const BASIS_POINTS_DENOMINATOR: i128 = 10_000;
 
+/// Default ceiling on the payout fee (10%)
+const DEFAULT_MAX_FEE_BASIS_POINTS: u32 = 1_000;
+
+/// Default time after a call's end_ts within which the oracle's price
+/// observation must fall (1 hour)
+const DEFAULT_SETTLEMENT_WINDOW: u64 = 3_600;
+
+/// Default time a submitted outcome stays provisional and open to challenge (1 day)
+const DEFAULT_DISPUTE_PERIOD: u64 = 86_400;
+
+/// Default bond a challenger escrows (1 unit of a 7-decimal Stellar asset)
+const DEFAULT_DISPUTE_BOND: i128 = 10_000_000;
+
+/// Time after the dispute period ends from which a call's unclaimed
+/// remainder can be swept to the treasury (90 days)
+const DUST_SWEEP_DELAY: u64 = 90 * 86_400;
+
+const DAY_IN_LEDGERS: u32 = 17_280;
+/// Default remaining TTL below which an entry is extended (7 days)
+const DEFAULT_TTL_THRESHOLD: u32 = 7 * DAY_IN_LEDGERS;
+/// Default TTL an entry is extended to (30 days)
+const DEFAULT_TTL_EXTEND_TO: u32 = 30 * DAY_IN_LEDGERS;
+
+/// Version byte prefixed to every signed oracle message
+const OUTCOME_MESSAGE_VERSION: u8 = 1;
+/// Domain tag separating outcome signatures from any other signed payload
+const OUTCOME_MESSAGE_DOMAIN: &[u8] = b"BACKIT_OUTCOME";
+/// Outcome byte signed by oracles to void a call (0 = NO, 1 = YES)
+const VOID_OUTCOME: u8 = 2;
+
+/// Privileged roles the owner can delegate; the owner holds every role
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Role {
+    /// May pause and unpause the contract
+    Pauser,
+    /// May change the fee configuration
+    FeeManager,
+    /// May authorize oracles and set the quorum threshold
+    OracleManager,
+    /// May resolve disputes and void calls
+    SettlementArbiter,
+}
+
+/// Per-call and per-account persistent storage keys
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Call(u64),
+    Withdrawal(u64, Address),
+    Dispute(u64),
+    Role(Role, Address),
+    /// Fee configuration snapshotted when the call was registered
+    CallFee(u64),
+    CreatorFee(u64),
+    /// Payout fees collected in a token and not yet withdrawn
+    AccruedFees(Address),
+    PayoutTotals(u64),
+}
+
 #[contracttype]
 #[derive(Clone, Debug, PartialEq)]
 pub struct CallData {
@@ -25,6 +107,16 @@ pub struct CallData {
     pub settled: bool,
     pub outcome: Option<bool>,
     pub final_price: Option<u128>,
+    /// Stake tokens held by this contract for the call and not yet paid out
+    pub escrow: u128,
+    /// End of the challenge period for a provisional outcome (0 until settled)
+    pub dispute_deadline: u64,
+    /// Whether the outcome is final and payouts are open
+    pub finalized: bool,
+    /// Whether the call was voided; every staker is refunded in full
+    pub voided: bool,
+    /// Whether the creator cancelled the call before anyone else staked
+    pub cancelled: bool,
 }
 
 #[contracttype]
@@ -39,7 +131,116 @@ pub struct StakeData {
 #[derive(Clone, Debug, PartialEq)]
 pub struct FeeConfig {
     pub basis_points: u32,
+    /// Receives slashed dispute bonds and swept dust
     pub treasury: Address,
+    pub mode: FeeMode,
+}
+
+/// What the payout fee is charged on
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FeeMode {
+    /// The whole payout, including the winner's returned stake
+    Gross,
+    /// Only the winnings taken from the losing side
+    Profit,
+}
+
+/// Fee terms a call's payouts are charged, fixed when it is registered
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeeSnapshot {
+    pub basis_points: u32,
+    pub mode: FeeMode,
+}
+
+/// What a call has paid out of its pool so far
+#[contracttype]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PayoutTotals {
+    /// Stake tokens paid out, including fees and creator rewards
+    pub paid: u128,
+    /// Winning-side stake whose payout has been withdrawn
+    pub claimed_stake: u128,
+}
+
+/// Share of the losing pool paid to a call's creator when YES wins
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct CreatorFee {
+    pub creator: Address,
+    pub basis_points: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct DisputeConfig {
+    /// Seconds a submitted outcome stays provisional
+    pub period: u64,
+    /// Bond a challenger escrows, in the call's stake token
+    pub bond: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct TtlConfig {
+    /// Remaining TTL, in ledgers, below which an entry is extended
+    pub threshold: u32,
+    /// TTL, in ledgers, an entry is extended to
+    pub extend_to: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dispute {
+    pub challenger: Address,
+    pub bond: i128,
+}
+
+/// Lifecycle of a call as seen by the outcome manager
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CallStatus {
+    /// Before end_ts; stakes are still accepted
+    Open,
+    /// Ended with only one side backed; every staker is refunded without a fee
+    Unopposed,
+    /// Ended and waiting for an oracle outcome
+    AwaitingOutcome,
+    /// Outcome submitted and still open to challenge
+    Provisional,
+    /// Outcome challenged and waiting for resolution
+    Disputed,
+    /// Outcome final; payouts are open
+    Final,
+    /// Call voided; every staker is refunded in full
+    Voided,
+    /// Call cancelled by its creator and refunded
+    Cancelled,
+}
+
+/// Result of one call in a `claim_many` batch
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ClaimResult {
+    /// Paid out; the amount is net of fees
+    Paid(u128),
+    /// The user already withdrew from the call
+    AlreadyClaimed,
+    /// The call has no final outcome and is not refundable yet
+    NotSettled,
+}
+
+/// Amounts owed for one withdrawal, in the call's stake token
+struct Payout {
+    token: Address,
+    net: i128,
+    /// Refund of a voided call
+    voided: bool,
+    /// Refund of an ended call with only one side backed
+    unopposed: bool,
+    /// Creator reward included in `net`
+    creator_reward: i128,
 }
 
 #[contracttype]
@@ -48,6 +249,24 @@ pub enum Event {
     OutcomeSubmitted(u64, bool, u128, BytesN<32>),
     PayoutWithdrawn(u64, Address, u128),
     OracleUpdated(BytesN<32>, bool),
+    QuorumOutcomeSubmitted(u64, bool, u128, Vec<BytesN<32>>),
+    OracleThresholdUpdated(u32),
+    OutcomeChallenged(u64, Address, i128),
+    OutcomeFinalized(u64, bool, u128),
+    DisputeResolved(u64, bool, u128, bool),
+    CallVoided(u64),
+    StakeRefunded(u64, Address, u128),
+    UnopposedRefunded(u64, Address, u128),
+    ContractUpgraded(BytesN<32>),
+    OwnerProposed(Address, Address),
+    OwnershipTransferred(Address, Address),
+    OwnerProposalCancelled(Address),
+    RoleGranted(Role, Address),
+    RoleRevoked(Role, Address),
+    SchemaMigrated(u32),
+    CreatorRewarded(u64, Address, u128),
+    FeesWithdrawn(Address, Address, i128),
+    DustSwept(u64, Address, u128),
 }
 
 #[contract]
@@ -56,8 +275,33 @@ pub struct OutcomeManagerContract;
 #[contractimpl]
 impl OutcomeManagerContract {
     fn require_owner_auth(env: &Env) {
-        let owner: Address = env.storage().instance().get(&OWNER).unwrap();
-        owner.require_auth();
+        Self::read_owner(env).require_auth();
+    }
+
+    fn read_owner(env: &Env) -> Address {
+        env.storage().instance().get(&OWNER).unwrap()
+    }
+
+    /// Require `caller` to authorize and to be the owner or hold `role`
+    fn require_role(env: &Env, role: Role, caller: &Address) {
+        caller.require_auth();
+        if *caller != Self::read_owner(env)
+            && !env
+                .storage()
+                .persistent()
+                .has(&DataKey::Role(role, caller.clone()))
+        {
+            panic!("Caller lacks role");
+        }
+    }
+
+    fn require_registry_auth(env: &Env) {
+        let call_registry: Address = env
+            .storage()
+            .instance()
+            .get(&CALL_REGISTRY)
+            .expect("Call registry not set");
+        call_registry.require_auth();
     }
 
     fn is_paused(env: &Env) -> bool {
@@ -77,331 +321,1604 @@ impl OutcomeManagerContract {
             .expect("Fee config not set")
     }
 
-    fn to_i128(value: u128) -> i128 {
-        i128::try_from(value).expect("Value exceeds i128 range")
+    fn get_max_fee(env: &Env) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&MAX_FEE)
+            .unwrap_or(DEFAULT_MAX_FEE_BASIS_POINTS)
     }
 
-    fn to_u128(value: i128) -> u128 {
-        u128::try_from(value).expect("Value must be non-negative")
+    /// The part of a fee config a call's snapshot keeps
+    fn snapshot_fee(fee_config: &FeeConfig) -> FeeSnapshot {
+        FeeSnapshot {
+            basis_points: fee_config.basis_points,
+            mode: fee_config.mode,
+        }
     }
 
-    /// Initialize the contract with owner and call registry address
-    pub fn initialize(env: Env, owner: Address, call_registry: Address) {
-        let storage = env.storage().instance();
+    /// Fee a call's payouts are charged: its snapshot, or the current config
+    /// for calls registered before snapshots were taken
+    fn get_call_fee_config(env: &Env, call_id: u64) -> FeeSnapshot {
+        env.storage()
+            .persistent()
+            .get(&DataKey::CallFee(call_id))
+            .unwrap_or_else(|| Self::snapshot_fee(&Self::get_fee_config(env)))
+    }
 
-        if storage.has(&OWNER) {
-            panic!("Contract already initialized");
-        }
+    fn registry_client(env: &Env) -> CallRegistryClient<'_> {
+        let call_registry: Address = env
+            .storage()
+            .instance()
+            .get(&CALL_REGISTRY)
+            .expect("Call registry not set");
+        CallRegistryClient::new(env, &call_registry)
+    }
 
-        storage.set(&OWNER, &owner);
-        storage.set(&CALL_REGISTRY, &call_registry);
+    fn get_dispute_config(env: &Env) -> DisputeConfig {
+        env.storage()
+            .persistent()
+            .get(&DISPUTE_CONFIG)
+            .unwrap_or(DisputeConfig {
+                period: DEFAULT_DISPUTE_PERIOD,
+                bond: DEFAULT_DISPUTE_BOND,
+            })
+    }
 
-        // Initialize empty oracles map
-        let oracles: Map<BytesN<32>, bool> = Map::new(&env);
-        storage.set(&ORACLES, &oracles);
+    /// Store a newly opened call holding its stakes in escrow, along with
+    /// a snapshot of the fee its payouts are charged
+    fn store_new_call(
+        env: &Env,
+        call_id: u64,
+        token: Address,
+        long_tokens: u128,
+        short_tokens: u128,
+        end_ts: u64,
+    ) {
+        let call_data = CallData {
+            id: call_id,
+            token,
+            long_tokens,
+            short_tokens,
+            end_ts,
+            settled: false,
+            outcome: None,
+            final_price: None,
+            escrow: long_tokens + short_tokens,
+            dispute_deadline: 0,
+            finalized: false,
+            voided: false,
+            cancelled: false,
+        };
 
-        // Initialize empty calls map
-        let calls: Map<u64, CallData> = Map::new(&env);
-        storage.set(&CALLS, &calls);
+        Self::save_call(env, &call_data);
 
-        // Initialize empty withdrawals tracking
-        let withdrawals: Map<(u64, Address), bool> = Map::new(&env);
-        storage.set(&WITHDRAWALS, &withdrawals);
+        // Payouts are charged the fee in force when the call was opened
+        let fee_key = DataKey::CallFee(call_id);
+        env.storage()
+            .persistent()
+            .set(&fee_key, &Self::snapshot_fee(&Self::get_fee_config(env)));
+        Self::extend_persistent(env, &fee_key);
+    }
 
-        // Initialize pause flag in persistent storage
-        env.storage().persistent().set(&IS_PAUSED, &false);
+    /// Contracts deployed before versioning have no stored version
+    fn read_schema_version(env: &Env) -> u32 {
+        env.storage().instance().get(&SCHEMA_VERSION).unwrap_or(1)
+    }
 
-        env.storage().persistent().set(
-            &FEE_CONFIG,
-            &FeeConfig {
-                basis_points: 0,
-                treasury: owner,
-            },
-        );
+    fn get_ttl_config(env: &Env) -> TtlConfig {
+        env.storage()
+            .instance()
+            .get(&TTL_CONFIG)
+            .unwrap_or(TtlConfig {
+                threshold: DEFAULT_TTL_THRESHOLD,
+                extend_to: DEFAULT_TTL_EXTEND_TO,
+            })
     }
 
-    pub fn set_fee_config(env: Env, basis_points: u32, treasury: Address) {
-        Self::require_owner_auth(&env);
+    /// Extend a persistent entry, and the contract instance and config it
+    /// depends on, so nothing an active call needs is archived
+    fn extend_persistent(env: &Env, key: &DataKey) {
+        let ttl = Self::get_ttl_config(env);
+        let storage = env.storage().persistent();
+        storage.extend_ttl(key, ttl.threshold, ttl.extend_to);
 
-        if basis_points > 10_000 {
-            panic!("Fee basis points cannot exceed 10000");
+        env.storage()
+            .instance()
+            .extend_ttl(ttl.threshold, ttl.extend_to);
+        for config_key in [
+            IS_PAUSED,
+            FEE_CONFIG,
+            MAX_FEE,
+            SETTLEMENT_WINDOW,
+            ORACLE_THRESHOLD,
+            DISPUTE_CONFIG,
+        ] {
+            if storage.has(&config_key) {
+                storage.extend_ttl(&config_key, ttl.threshold, ttl.extend_to);
+            }
         }
-
-        env.storage().persistent().set(
-            &FEE_CONFIG,
-            &FeeConfig {
-                basis_points,
-                treasury,
-            },
-        );
     }
 
-    pub fn get_fee_config_view(env: Env) -> FeeConfig {
-        Self::get_fee_config(&env)
+    fn load_call(env: &Env, call_id: u64) -> CallData {
+        let key = DataKey::Call(call_id);
+        let call_data = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or_else(|| panic!("Call not found"));
+        Self::extend_persistent(env, &key);
+        call_data
     }
 
-    /// Pause write operations (owner only)
-    pub fn pause(env: Env) {
-        Self::require_owner_auth(&env);
-        env.storage().persistent().set(&IS_PAUSED, &true);
+    fn save_call(env: &Env, call_data: &CallData) {
+        let key = DataKey::Call(call_data.id);
+        env.storage().persistent().set(&key, call_data);
+        Self::extend_persistent(env, &key);
     }
 
-    /// Resume write operations (owner only)
-    pub fn unpause(env: Env) {
-        Self::require_owner_auth(&env);
-        env.storage().persistent().set(&IS_PAUSED, &false);
+    fn is_withdrawn(env: &Env, call_id: u64, user: &Address) -> bool {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Withdrawal(call_id, user.clone()))
+            .unwrap_or(false)
     }
 
-    pub fn get_is_paused(env: Env) -> bool {
-        Self::is_paused(&env)
+    fn mark_withdrawn(env: &Env, call_id: u64, user: &Address) {
+        let key = DataKey::Withdrawal(call_id, user.clone());
+        env.storage().persistent().set(&key, &true);
+        Self::extend_persistent(env, &key);
     }
 
-    /// Set oracle authorization status (owner only)
-    pub fn set_oracle(env: Env, oracle: BytesN<32>, authorized: bool) {
-        let storage = env.storage().instance();
-        Self::require_owner_auth(&env);
-
-        let mut oracles: Map<BytesN<32>, bool> = storage.get(&ORACLES).unwrap();
-        oracles.set(oracle.clone(), authorized);
-        storage.set(&ORACLES, &oracles);
+    fn read_accrued_fees(env: &Env, token: &Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::AccruedFees(token.clone()))
+            .unwrap_or(0)
+    }
 
-        env.events().publish(
-            (Symbol::new(&env, "oracle_updated"),),
-            Event::OracleUpdated(oracle, authorized),
-        );
+    fn write_accrued_fees(env: &Env, token: &Address, amount: i128) {
+        let key = DataKey::AccruedFees(token.clone());
+        env.storage().persistent().set(&key, &amount);
+        Self::extend_persistent(env, &key);
     }
 
-    /// Check if an oracle is authorized
-    pub fn is_authorized_oracle(env: Env, oracle: BytesN<32>) -> bool {
-        let storage = env.storage().instance();
-        let oracles: Map<BytesN<32>, bool> =
-            storage.get(&ORACLES).unwrap_or_else(|| Map::new(&env));
-        oracles.get(oracle).unwrap_or(false)
+    fn read_payout_totals(env: &Env, call_id: u64) -> PayoutTotals {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PayoutTotals(call_id))
+            .unwrap_or_default()
     }
 
-    /// Submit outcome with ed25519 signature verification
-    pub fn submit_outcome(
-        env: Env,
-        call_id: u64,
-        outcome: bool,
-        final_price: u128,
-        timestamp: u64,
-        oracle_pubkey: BytesN<32>,
-        signature: BytesN<64>,
-    ) -> bool {
-        Self::assert_not_paused(&env);
-        let storage = env.storage().instance();
+    fn write_payout_totals(env: &Env, call_id: u64, totals: &PayoutTotals) {
+        let key = DataKey::PayoutTotals(call_id);
+        env.storage().persistent().set(&key, totals);
+        Self::extend_persistent(env, &key);
+    }
 
-        // Verify call hasn't been settled
-        let mut calls: Map<u64, CallData> = storage.get(&CALLS).unwrap_or_else(|| Map::new(&env));
+    fn read_creator_fee(env: &Env, call_id: u64) -> Option<CreatorFee> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::CreatorFee(call_id))
+    }
 
-        if let Some(call_data) = calls.get(call_id) {
-            if call_data.settled {
-                panic!("Call already settled");
-            }
-        } else {
-            panic!("Call not found");
-        }
-
-        // Construct message for signature verification
-        // Format: call_id (8 bytes) + outcome (1 byte) + final_price (16 bytes) + timestamp (8 bytes)
-        let mut message = Bytes::new(&env);
-
-        // Add call_id (u64 big-endian)
-        message.push_back((call_id >> 56) as u8);
-        message.push_back(((call_id >> 48) & 0xFF) as u8);
-        message.push_back(((call_id >> 40) & 0xFF) as u8);
-        message.push_back(((call_id >> 32) & 0xFF) as u8);
-        message.push_back(((call_id >> 24) & 0xFF) as u8);
-        message.push_back(((call_id >> 16) & 0xFF) as u8);
-        message.push_back(((call_id >> 8) & 0xFF) as u8);
-        message.push_back((call_id & 0xFF) as u8);
-
-        // Add outcome (1 byte)
-        message.push_back(if outcome { 1u8 } else { 0u8 });
-
-        // Add final_price (u128 big-endian, 16 bytes)
-        for i in (0..16).rev() {
-            message.push_back(((final_price >> (i * 8)) & 0xFF) as u8);
-        }
-
-        // Add timestamp (u64 big-endian)
-        message.push_back((timestamp >> 56) as u8);
-        message.push_back(((timestamp >> 48) & 0xFF) as u8);
-        message.push_back(((timestamp >> 40) & 0xFF) as u8);
-        message.push_back(((timestamp >> 32) & 0xFF) as u8);
-        message.push_back(((timestamp >> 24) & 0xFF) as u8);
-        message.push_back(((timestamp >> 16) & 0xFF) as u8);
-        message.push_back(((timestamp >> 8) & 0xFF) as u8);
-        message.push_back((timestamp & 0xFF) as u8);
-
-        // Verify ed25519 signature
-        env.crypto()
-            .ed25519_verify(&oracle_pubkey, &message, &signature);
-
-        // Verify signer is an authorized oracle
-        let oracles: Map<BytesN<32>, bool> =
-            storage.get(&ORACLES).unwrap_or_else(|| Map::new(&env));
-        let is_authorized = oracles.get(oracle_pubkey.clone()).unwrap_or(false);
+    fn read_dispute(env: &Env, call_id: u64) -> Option<Dispute> {
+        env.storage().persistent().get(&DataKey::Dispute(call_id))
+    }
 
-        if !is_authorized {
-            panic!("Oracle not authorized");
+    /// An outcome is final once resolved, or once its dispute period has
+    /// passed without a challenge
+    fn is_outcome_final(env: &Env, call_data: &CallData) -> bool {
+        if call_data.finalized {
+            return true;
         }
+        call_data.settled
+            && env.ledger().timestamp() > call_data.dispute_deadline
+            && !env
+                .storage()
+                .persistent()
+                .has(&DataKey::Dispute(call_data.id))
+    }
 
-        // Mark call as settled
-        let mut call_data = calls.get(call_id).unwrap();
-        call_data.settled = true;
-        call_data.outcome = Some(outcome);
-        call_data.final_price = Some(final_price);
-        calls.set(call_id, call_data);
-        storage.set(&CALLS, &calls);
+    /// A call is unopposed when nobody staked on one of its sides
+    fn is_unopposed(call_data: &CallData) -> bool {
+        call_data.long_tokens == 0 || call_data.short_tokens == 0
+    }
 
-        // Emit event
-        env.events().publish(
-            (Symbol::new(&env, "outcome_submitted"),),
-            Event::OutcomeSubmitted(call_id, outcome, final_price, oracle_pubkey),
-        );
+    fn get_oracle_threshold(env: &Env) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&ORACLE_THRESHOLD)
+            .unwrap_or(1)
+    }
 
-        true
+    fn get_settlement_window(env: &Env) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&SETTLEMENT_WINDOW)
+            .unwrap_or(DEFAULT_SETTLEMENT_WINDOW)
     }
 
-    /// Register a call (called by CallRegistry or stake contract)
-    pub fn register_call(
-        env: Env,
-        call_id: u64,
-        token: Address,
-        long_tokens: u128,
-        short_tokens: u128,
-        end_ts: u64,
-    ) {
-        let storage = env.storage().instance();
-        let mut calls: Map<u64, CallData> = storage.get(&CALLS).unwrap_or_else(|| Map::new(&env));
+    fn to_i128(value: u128) -> i128 {
+        i128::try_from(value).expect("Value exceeds i128 range")
+    }
 
-        let call_data = CallData {
-            id: call_id,
-            token,
-            long_tokens,
-            short_tokens,
-            end_ts,
-            settled: false,
-            outcome: None,
-            final_price: None,
-        };
+    fn to_u128(value: i128) -> u128 {
+        u128::try_from(value).expect("Value must be non-negative")
+    }
 
-        calls.set(call_id, call_data);
-        storage.set(&CALLS, &calls);
+    fn build_outcome_message(
+        env: &Env,
+        call_id: u64,
+        outcome: Option<bool>,
+        final_price: u128,
+        timestamp: u64,
+    ) -> Bytes {
+        let mut message = Bytes::new(env);
+
+        // Bind the signature to this message version, network and deployment
+        message.push_back(OUTCOME_MESSAGE_VERSION);
+        message.extend_from_slice(OUTCOME_MESSAGE_DOMAIN);
+        message.append(&env.ledger().network_id().into());
+        message.append(&env.current_contract_address().to_xdr(env));
+
+        message.extend_from_array(&call_id.to_be_bytes());
+        message.push_back(match outcome {
+            Some(true) => 1u8,
+            Some(false) => 0u8,
+            None => VOID_OUTCOME,
+        });
+        message.extend_from_array(&final_price.to_be_bytes());
+        message.extend_from_array(&timestamp.to_be_bytes());
+
+        message
     }
 
-    /// Withdraw payout for a settled call
-    pub fn withdraw_payout(
-        env: Env,
+    /// Validate the call and oracle signatures, then mark the call settled
+    /// A `None` outcome voids the call
+    /// Returns the verified signer keys
+    fn settle_with_signatures(
+        env: &Env,
         call_id: u64,
-        user: Address,
-        user_stake: u128,
-        user_side: bool,
-    ) -> u128 {
-        let storage = env.storage().instance();
-        user.require_auth();
+        outcome: Option<bool>,
+        final_price: u128,
+        timestamp: u64,
+        signatures: &Vec<(BytesN<32>, BytesN<64>)>,
+    ) -> Vec<BytesN<32>> {
+        Self::assert_not_paused(env);
 
-        // Check if user already withdrew
-        let withdrawals: Map<(u64, Address), bool> =
-            storage.get(&WITHDRAWALS).unwrap_or_else(|| Map::new(&env));
+        // Verify call hasn't been settled
+        let mut call_data = Self::load_call(env, call_id);
+        if call_data.settled {
+            panic!("Call already settled");
+        }
 
-        if let Some(withdrawn) = withdrawals.get((call_id, user.clone())) {
-            if withdrawn {
-                panic!("Already withdrawn");
-            }
+        // The call must have ended and the price must be observed within
+        // the settlement window after end_ts
+        let now = env.ledger().timestamp();
+        if now < call_data.end_ts {
+            panic!("Call not ended");
+        }
+        if timestamp < call_data.end_ts {
+            panic!("Observation before call end");
+        }
+        if timestamp > now {
+            panic!("Observation in the future");
+        }
+        if timestamp - call_data.end_ts > Self::get_settlement_window(env) {
+            panic!("Observation outside settlement window");
         }
 
-        // Get call data
-        let calls: Map<u64, CallData> = storage.get(&CALLS).unwrap_or_else(|| Map::new(&env));
-        let call_data = calls
-            .get(call_id)
-            .unwrap_or_else(|| panic!("Call not found"));
+        // Construct the domain-separated message for signature verification
+        let message = Self::build_outcome_message(env, call_id, outcome, final_price, timestamp);
 
-        // Verify call is settled
-        if !call_data.settled {
-            panic!("Call not settled");
-        }
+        let oracles: Map<BytesN<32>, bool> = env
+            .storage()
+            .instance()
+            .get(&ORACLES)
+            .unwrap_or_else(|| Map::new(env));
+        let mut signers: Vec<BytesN<32>> = Vec::new(env);
 
-        let outcome = call_data.outcome.unwrap();
-        let gross_payout: i128 = if user_side == outcome {
-            // User won - calculate their share
-            let winning_tokens = if outcome {
-                Self::to_i128(call_data.long_tokens)
-            } else {
-                Self::to_i128(call_data.short_tokens)
-            };
+        for (oracle_pubkey, signature) in signatures.iter() {
+            if signers.contains(&oracle_pubkey) {
+                panic!("Duplicate oracle signature");
+            }
 
-            let losing_tokens = if outcome {
-                Self::to_i128(call_data.short_tokens)
-            } else {
-                Self::to_i128(call_data.long_tokens)
-            };
+            // Verify ed25519 signature
+            env.crypto()
+                .ed25519_verify(&oracle_pubkey, &message, &signature);
 
-            let user_stake_i128 = Self::to_i128(user_stake);
+            // Verify signer is an authorized oracle
+            let is_authorized = oracles.get(oracle_pubkey.clone()).unwrap_or(false);
 
-            // User gets their stake back + their share of losing side
-            user_stake_i128 + ((user_stake_i128 * losing_tokens) / winning_tokens)
-        } else {
-            // User lost - no payout (their stake is already gone)
-            0
+            if !is_authorized {
+                panic!("Oracle not authorized");
+            }
+
+            signers.push_back(oracle_pubkey);
+        }
+
+        if signers.len() < Self::get_oracle_threshold(env) {
+            panic!("Oracle quorum not reached");
+        }
+
+        // Mark call as settled; the outcome stays provisional for the dispute
+        // period while a void is final immediately
+        call_data.settled = true;
+        if outcome.is_some() {
+            let dispute_period = Self::get_dispute_config(env).period;
+            call_data.outcome = outcome;
+            call_data.final_price = Some(final_price);
+            call_data.dispute_deadline = now + dispute_period;
+            call_data.finalized = dispute_period == 0;
+        } else {
+            call_data.voided = true;
+            call_data.dispute_deadline = now;
+            call_data.finalized = true;
+        }
+        Self::save_call(env, &call_data);
+
+        signers
+    }
+
+    /// An ended call with only one side backed has nobody to win from, so it
+    /// is refunded without waiting for an outcome
+    fn is_unopposed_refund(env: &Env, call_data: &CallData) -> bool {
+        !call_data.voided
+            && Self::is_unopposed(call_data)
+            && env.ledger().timestamp() >= call_data.end_ts
+    }
+
+    /// Whether payouts are open: the call is refunded, or settled with an
+    /// outcome that can no longer be disputed
+    fn is_claimable(env: &Env, call_data: &CallData) -> bool {
+        Self::is_unopposed_refund(env, call_data)
+            || (call_data.settled && Self::is_outcome_final(env, call_data))
+    }
+
+    /// Record a user's share of a call given their stake on each side
+    /// Takes the payout out of the call's escrow, accrues its fee and marks the
+    /// user withdrawn; the caller transfers the returned amount and publishes
+    /// the event
+    /// Winnings are pro-rata over the call's recorded pool totals, after the
+    /// creator's share of the losing pool when YES wins; the creator receives
+    /// that share in full with their own payout
+    fn record_payout(
+        env: &Env,
+        call_id: u64,
+        user: &Address,
+        stake_yes: i128,
+        stake_no: i128,
+    ) -> Payout {
+        // Check if user already withdrew
+        if Self::is_withdrawn(env, call_id, user) {
+            panic!("Already withdrawn");
+        }
+
+        // Get call data
+        let mut call_data = Self::load_call(env, call_id);
+
+        let unopposed = Self::is_unopposed_refund(env, &call_data);
+        let refund = call_data.voided || unopposed;
+
+        // Otherwise verify call is settled and the outcome can no longer be disputed
+        if !unopposed {
+            if !call_data.settled {
+                panic!("Call not settled");
+            }
+            if !Self::is_outcome_final(env, &call_data) {
+                panic!("Outcome not final");
+            }
+        }
+
+        let mut creator_reward: i128 = 0;
+        let mut claimed_stake: i128 = 0;
+        let (gross_payout, winnings): (i128, i128) = if refund {
+            // Voided or unopposed call - refund both sides of the user's stake
+            (stake_yes + stake_no, 0)
+        } else {
+            // Persist a final outcome the first time it pays out
+            call_data.finalized = true;
+
+            let outcome = call_data.outcome.unwrap();
+            let user_stake = if outcome { stake_yes } else { stake_no };
+            let (winning_tokens, losing_tokens) = if outcome {
+                (
+                    Self::to_i128(call_data.long_tokens),
+                    Self::to_i128(call_data.short_tokens),
+                )
+            } else {
+                (
+                    Self::to_i128(call_data.short_tokens),
+                    Self::to_i128(call_data.long_tokens),
+                )
+            };
+
+            // The creator backs YES, so their fee is only taken when YES wins
+            let mut shared_pool = losing_tokens;
+            if let (true, Some(creator_fee)) = (outcome, Self::read_creator_fee(env, call_id)) {
+                let reward = losing_tokens
+                    .checked_mul(i128::from(creator_fee.basis_points))
+                    .expect("Fee multiplication overflow")
+                    / BASIS_POINTS_DENOMINATOR;
+                shared_pool -= reward;
+                if creator_fee.creator == *user {
+                    creator_reward = reward;
+                }
+            }
+
+            if user_stake > 0 {
+                claimed_stake = user_stake;
+
+                // User gets their stake back + their share of losing side
+                let winnings = user_stake
+                    .checked_mul(shared_pool)
+                    .expect("Payout multiplication overflow")
+                    / winning_tokens;
+                (user_stake + winnings, winnings)
+            } else {
+                // User has no stake on the winning side - no payout
+                (0, 0)
+            }
         };
 
-        let fee_config = Self::get_fee_config(&env);
-        let fee_amount = if gross_payout > 0 {
-            gross_payout
+        // Refunds are never charged a fee
+        let fee_config = Self::get_call_fee_config(env, call_id);
+        let fee_base = match fee_config.mode {
+            FeeMode::Gross => gross_payout,
+            FeeMode::Profit => winnings,
+        };
+        let fee_amount = if fee_base > 0 && !refund {
+            fee_base
                 .checked_mul(i128::from(fee_config.basis_points))
                 .expect("Fee multiplication overflow")
                 / BASIS_POINTS_DENOMINATOR
         } else {
             0
         };
-        let net_payout = gross_payout - fee_amount;
+        let net_payout = gross_payout - fee_amount + creator_reward;
+
+        // Payouts can only come out of the call's own escrow
+        let paid_out = Self::to_u128(gross_payout + creator_reward);
+        if paid_out > call_data.escrow {
+            panic!("Insufficient escrow");
+        }
+        call_data.escrow -= paid_out;
+        Self::save_call(env, &call_data);
+
+        let mut totals = Self::read_payout_totals(env, call_id);
+        totals.paid += paid_out;
+        totals.claimed_stake += Self::to_u128(claimed_stake);
+        Self::write_payout_totals(env, call_id, &totals);
+
+        // Fees stay in the contract until withdrawn with `withdraw_fees`
+        if fee_amount > 0 {
+            let accrued = Self::read_accrued_fees(env, &call_data.token);
+            Self::write_accrued_fees(env, &call_data.token, accrued + fee_amount);
+        }
 
         // Mark withdrawal as done
-        let mut new_withdrawals = withdrawals.clone();
-        new_withdrawals.set((call_id, user.clone()), true);
-        storage.set(&WITHDRAWALS, &new_withdrawals);
+        Self::mark_withdrawn(env, call_id, user);
+
+        Payout {
+            token: call_data.token,
+            net: net_payout,
+            voided: call_data.voided,
+            unopposed,
+            creator_reward,
+        }
+    }
 
-        if net_payout > 0 {
-            let token_client = token::Client::new(&env, &call_data.token);
-            token_client.transfer(&env.current_contract_address(), &user, &net_payout);
+    fn publish_payout(env: &Env, call_id: u64, user: &Address, payout: &Payout) {
+        let amount = Self::to_u128(payout.net);
+        if payout.voided {
+            env.events().publish(
+                (Symbol::new(env, "stake_refunded"),),
+                Event::StakeRefunded(call_id, user.clone(), amount),
+            );
+        } else if payout.unopposed {
+            env.events().publish(
+                (Symbol::new(env, "unopposed_refunded"),),
+                Event::UnopposedRefunded(call_id, user.clone(), amount),
+            );
+        } else {
+            env.events().publish(
+                (Symbol::new(env, "payout_withdrawn"),),
+                Event::PayoutWithdrawn(call_id, user.clone(), amount),
+            );
+        }
 
-            if fee_amount > 0 {
-                token_client.transfer(
-                    &env.current_contract_address(),
-                    &fee_config.treasury,
-                    &fee_amount,
-                );
-            }
+        if payout.creator_reward > 0 {
+            env.events().publish(
+                (Symbol::new(env, "creator_rewarded"),),
+                Event::CreatorRewarded(call_id, user.clone(), Self::to_u128(payout.creator_reward)),
+            );
         }
+    }
 
-        let payout = Self::to_u128(net_payout);
+    /// Pay a user's share of a call and mark them withdrawn
+    /// Returns the payout net of fees
+    fn pay_out(env: &Env, call_id: u64, user: Address, stake_yes: i128, stake_no: i128) -> u128 {
+        let payout = Self::record_payout(env, call_id, &user, stake_yes, stake_no);
+        if payout.net > 0 {
+            token::Client::new(env, &payout.token).transfer(
+                &env.current_contract_address(),
+                &user,
+                &payout.net,
+            );
+        }
+        Self::publish_payout(env, call_id, &user, &payout);
+        Self::to_u128(payout.net)
+    }
+
+    /// Pay a user's share of a call using their stakes in the CallRegistry
+    fn pay_out_registry_stakes(env: &Env, call_id: u64, user: Address) -> u128 {
+        let registry = Self::registry_client(env);
+        let stake_yes = registry.get_user_stake(&call_id, &user, &true);
+        let stake_no = registry.get_user_stake(&call_id, &user, &false);
+
+        Self::pay_out(env, call_id, user, stake_yes, stake_no)
+    }
+
+    /// Initialize the contract with owner and call registry address
+    pub fn initialize(env: Env, owner: Address, call_registry: Address) {
+        let storage = env.storage().instance();
+
+        if storage.has(&OWNER) {
+            panic!("Contract already initialized");
+        }
+
+        storage.set(&OWNER, &owner);
+        storage.set(&CALL_REGISTRY, &call_registry);
+        storage.set(&SCHEMA_VERSION, &CURRENT_SCHEMA_VERSION);
+
+        // Initialize empty oracles map
+        let oracles: Map<BytesN<32>, bool> = Map::new(&env);
+        storage.set(&ORACLES, &oracles);
+
+        // Initialize pause flag in persistent storage
+        env.storage().persistent().set(&IS_PAUSED, &false);
+
+        env.storage().persistent().set(
+            &FEE_CONFIG,
+            &FeeConfig {
+                basis_points: 0,
+                treasury: owner,
+                mode: FeeMode::Gross,
+            },
+        );
+
+        env.storage()
+            .persistent()
+            .set(&SETTLEMENT_WINDOW, &DEFAULT_SETTLEMENT_WINDOW);
+        env.storage().persistent().set(&ORACLE_THRESHOLD, &1u32);
+        env.storage().persistent().set(
+            &DISPUTE_CONFIG,
+            &DisputeConfig {
+                period: DEFAULT_DISPUTE_PERIOD,
+                bond: DEFAULT_DISPUTE_BOND,
+            },
+        );
+    }
+
+    /// Propose a new owner (owner only)
+    /// The proposal takes effect once the new owner calls `accept_owner`
+    /// A later proposal replaces a pending one
+    pub fn propose_owner(env: Env, new_owner: Address) {
+        Self::require_owner_auth(&env);
+
+        let storage = env.storage().instance();
+        let owner: Address = storage.get(&OWNER).unwrap();
+        storage.set(&PENDING_OWNER, &new_owner);
 
-        // Emit event
         env.events().publish(
-            (Symbol::new(&env, "payout_withdrawn"),),
-            Event::PayoutWithdrawn(call_id, user, payout),
+            (Symbol::new(&env, "owner_proposed"),),
+            Event::OwnerProposed(owner, new_owner),
         );
+    }
+
+    /// Accept a pending owner proposal (proposed owner only)
+    pub fn accept_owner(env: Env) {
+        let storage = env.storage().instance();
+        let new_owner: Address = storage.get(&PENDING_OWNER).expect("No pending owner");
+        new_owner.require_auth();
+
+        let old_owner: Address = storage.get(&OWNER).unwrap();
+        storage.set(&OWNER, &new_owner);
+        storage.remove(&PENDING_OWNER);
 
-        payout
+        env.events().publish(
+            (Symbol::new(&env, "ownership_transferred"),),
+            Event::OwnershipTransferred(old_owner, new_owner),
+        );
     }
 
-    /// Get call data (view function)
-    pub fn get_call(env: Env, call_id: u64) -> Option<CallData> {
+    /// Withdraw a pending owner proposal (owner only)
+    pub fn cancel_owner_proposal(env: Env) {
+        Self::require_owner_auth(&env);
+
         let storage = env.storage().instance();
-        let calls: Map<u64, CallData> = storage.get(&CALLS).unwrap_or_else(|| Map::new(&env));
-        calls.get(call_id)
+        let pending: Address = storage.get(&PENDING_OWNER).expect("No pending owner");
+        storage.remove(&PENDING_OWNER);
+
+        env.events().publish(
+            (Symbol::new(&env, "owner_proposal_cancelled"),),
+            Event::OwnerProposalCancelled(pending),
+        );
     }
 
-    /// Check if user already withdrew from a call
-    pub fn has_withdrawn(env: Env, call_id: u64, user: Address) -> bool {
+    pub fn get_owner(env: Env) -> Address {
+        Self::read_owner(&env)
+    }
+
+    pub fn get_pending_owner(env: Env) -> Option<Address> {
+        env.storage().instance().get(&PENDING_OWNER)
+    }
+
+    /// Grant a role to an account (owner only)
+    pub fn grant_role(env: Env, role: Role, account: Address) {
+        Self::require_owner_auth(&env);
+
+        let key = DataKey::Role(role, account.clone());
+        env.storage().persistent().set(&key, &true);
+        Self::extend_persistent(&env, &key);
+
+        env.events().publish(
+            (Symbol::new(&env, "role_granted"),),
+            Event::RoleGranted(role, account),
+        );
+    }
+
+    /// Revoke a role from an account (owner only)
+    pub fn revoke_role(env: Env, role: Role, account: Address) {
+        Self::require_owner_auth(&env);
+
+        let key = DataKey::Role(role, account.clone());
+        if !env.storage().persistent().has(&key) {
+            panic!("Role not granted");
+        }
+        env.storage().persistent().remove(&key);
+
+        env.events().publish(
+            (Symbol::new(&env, "role_revoked"),),
+            Event::RoleRevoked(role, account),
+        );
+    }
+
+    /// Whether an account may act in a role; the owner holds every role
+    pub fn has_role(env: Env, role: Role, account: Address) -> bool {
+        account == Self::read_owner(&env)
+            || env
+                .storage()
+                .persistent()
+                .has(&DataKey::Role(role, account))
+    }
+
+    /// Set the fee charged on payouts of calls registered from now on
+    /// (owner or fee manager)
+    /// Existing calls keep the fee they were registered with
+    pub fn set_fee_config(
+        env: Env,
+        caller: Address,
+        basis_points: u32,
+        treasury: Address,
+        mode: FeeMode,
+    ) {
+        Self::require_role(&env, Role::FeeManager, &caller);
+
+        if basis_points > Self::get_max_fee(&env) {
+            panic!("Fee exceeds max fee");
+        }
+
+        env.storage().persistent().set(
+            &FEE_CONFIG,
+            &FeeConfig {
+                basis_points,
+                treasury,
+                mode,
+            },
+        );
+    }
+
+    pub fn get_fee_config_view(env: Env) -> FeeConfig {
+        Self::get_fee_config(&env)
+    }
+
+    /// Set the ceiling `set_fee_config` enforces, in basis points (owner only)
+    pub fn set_max_fee(env: Env, basis_points: u32) {
+        Self::require_owner_auth(&env);
+
+        if basis_points > 10_000 {
+            panic!("Fee basis points cannot exceed 10000");
+        }
+        if Self::get_fee_config(&env).basis_points > basis_points {
+            panic!("Current fee exceeds max fee");
+        }
+
+        env.storage().persistent().set(&MAX_FEE, &basis_points);
+    }
+
+    pub fn get_max_fee_view(env: Env) -> u32 {
+        Self::get_max_fee(&env)
+    }
+
+    /// Payout fees collected in a token and not yet withdrawn (view function)
+    pub fn accrued_fees(env: Env, token: Address) -> i128 {
+        Self::read_accrued_fees(&env, &token)
+    }
+
+    /// Withdraw accrued payout fees in a token (owner or fee manager)
+    pub fn withdraw_fees(env: Env, caller: Address, token: Address, to: Address, amount: i128) {
+        Self::require_role(&env, Role::FeeManager, &caller);
+
+        if amount <= 0 {
+            panic!("Amount must be > 0");
+        }
+        let accrued = Self::read_accrued_fees(&env, &token);
+        if amount > accrued {
+            panic!("Insufficient accrued fees");
+        }
+        Self::write_accrued_fees(&env, &token, accrued - amount);
+
+        token::Client::new(&env, &token).transfer(&env.current_contract_address(), &to, &amount);
+
+        env.events().publish(
+            (Symbol::new(&env, "fees_withdrawn"),),
+            Event::FeesWithdrawn(token, to, amount),
+        );
+    }
+
+    /// Get the fee a call's payouts are charged (view function)
+    pub fn get_call_fee(env: Env, call_id: u64) -> FeeSnapshot {
+        Self::get_call_fee_config(&env, call_id)
+    }
+
+    /// Set how long after a call's end_ts an oracle observation stays valid (owner only)
+    pub fn set_settlement_window(env: Env, window: u64) {
+        Self::require_owner_auth(&env);
+
+        if window == 0 {
+            panic!("Settlement window must be > 0");
+        }
+
+        env.storage().persistent().set(&SETTLEMENT_WINDOW, &window);
+    }
+
+    pub fn get_settlement_window_view(env: Env) -> u64 {
+        Self::get_settlement_window(&env)
+    }
+
+    /// Set the dispute period and challenge bond (owner only)
+    pub fn set_dispute_config(env: Env, period: u64, bond: i128) {
+        Self::require_owner_auth(&env);
+
+        if bond <= 0 {
+            panic!("Dispute bond must be > 0");
+        }
+
+        env.storage()
+            .persistent()
+            .set(&DISPUTE_CONFIG, &DisputeConfig { period, bond });
+    }
+
+    pub fn get_dispute_config_view(env: Env) -> DisputeConfig {
+        Self::get_dispute_config(&env)
+    }
+
+    /// Pause write operations (owner or pauser)
+    pub fn pause(env: Env, caller: Address) {
+        Self::require_role(&env, Role::Pauser, &caller);
+        env.storage().persistent().set(&IS_PAUSED, &true);
+    }
+
+    /// Resume write operations (owner or pauser)
+    pub fn unpause(env: Env, caller: Address) {
+        Self::require_role(&env, Role::Pauser, &caller);
+        env.storage().persistent().set(&IS_PAUSED, &false);
+    }
+
+    pub fn get_is_paused(env: Env) -> bool {
+        Self::is_paused(&env)
+    }
+
+    /// Set oracle authorization status (owner or oracle manager)
+    pub fn set_oracle(env: Env, caller: Address, oracle: BytesN<32>, authorized: bool) {
+        let storage = env.storage().instance();
+        Self::require_role(&env, Role::OracleManager, &caller);
+
+        let mut oracles: Map<BytesN<32>, bool> = storage.get(&ORACLES).unwrap();
+        oracles.set(oracle.clone(), authorized);
+        storage.set(&ORACLES, &oracles);
+
+        env.events().publish(
+            (Symbol::new(&env, "oracle_updated"),),
+            Event::OracleUpdated(oracle, authorized),
+        );
+    }
+
+    /// Set how many distinct oracle signatures settle a call
+    /// (owner or oracle manager)
+    pub fn set_oracle_threshold(env: Env, caller: Address, threshold: u32) {
+        Self::require_role(&env, Role::OracleManager, &caller);
+
+        if threshold == 0 {
+            panic!("Oracle threshold must be > 0");
+        }
+
+        env.storage()
+            .persistent()
+            .set(&ORACLE_THRESHOLD, &threshold);
+
+        env.events().publish(
+            (Symbol::new(&env, "oracle_threshold_updated"),),
+            Event::OracleThresholdUpdated(threshold),
+        );
+    }
+
+    pub fn get_oracle_threshold_view(env: Env) -> u32 {
+        Self::get_oracle_threshold(&env)
+    }
+
+    /// Check if an oracle is authorized
+    pub fn is_authorized_oracle(env: Env, oracle: BytesN<32>) -> bool {
         let storage = env.storage().instance();
-        let withdrawals: Map<(u64, Address), bool> =
-            storage.get(&WITHDRAWALS).unwrap_or_else(|| Map::new(&env));
+        let oracles: Map<BytesN<32>, bool> =
+            storage.get(&ORACLES).unwrap_or_else(|| Map::new(&env));
+        oracles.get(oracle).unwrap_or(false)
+    }
+
+    /// Build the message an oracle signs for `submit_outcome`
+    /// Format: version (1 byte) + domain tag + network id (32 bytes)
+    /// + contract address (ScVal XDR) + call_id (8 bytes) + outcome (1 byte)
+    /// + final_price (16 bytes) + timestamp (8 bytes), integers big-endian
+    pub fn outcome_message(
+        env: Env,
+        call_id: u64,
+        outcome: bool,
+        final_price: u128,
+        timestamp: u64,
+    ) -> Bytes {
+        Self::build_outcome_message(&env, call_id, Some(outcome), final_price, timestamp)
+    }
+
+    /// Build the message an oracle signs for `submit_void`
+    /// Same format as `outcome_message` with outcome byte 2 and a zero price
+    pub fn void_message(env: Env, call_id: u64, timestamp: u64) -> Bytes {
+        Self::build_outcome_message(&env, call_id, None, 0, timestamp)
+    }
+
+    /// Submit outcome with ed25519 signature verification
+    /// Only valid while the oracle threshold is 1
+    pub fn submit_outcome(
+        env: Env,
+        call_id: u64,
+        outcome: bool,
+        final_price: u128,
+        timestamp: u64,
+        oracle_pubkey: BytesN<32>,
+        signature: BytesN<64>,
+    ) -> bool {
+        let signatures = vec![&env, (oracle_pubkey.clone(), signature)];
+        Self::settle_with_signatures(
+            &env,
+            call_id,
+            Some(outcome),
+            final_price,
+            timestamp,
+            &signatures,
+        );
+
+        // Emit event
+        env.events().publish(
+            (Symbol::new(&env, "outcome_submitted"),),
+            Event::OutcomeSubmitted(call_id, outcome, final_price, oracle_pubkey),
+        );
+
+        true
+    }
+
+    /// Submit outcome signed by a quorum of oracles
+    /// Each (pubkey, signature) pair must come from a distinct authorized oracle
+    /// and at least the configured threshold of them must sign the same message
+    pub fn submit_outcome_multi(
+        env: Env,
+        call_id: u64,
+        outcome: bool,
+        final_price: u128,
+        timestamp: u64,
+        signatures: Vec<(BytesN<32>, BytesN<64>)>,
+    ) -> bool {
+        let signers = Self::settle_with_signatures(
+            &env,
+            call_id,
+            Some(outcome),
+            final_price,
+            timestamp,
+            &signatures,
+        );
+
+        // Emit event
+        env.events().publish(
+            (Symbol::new(&env, "outcome_submitted"),),
+            Event::QuorumOutcomeSubmitted(call_id, outcome, final_price, signers),
+        );
+
+        true
+    }
+
+    /// Void a call with oracle signatures, e.g. for a delisted pair or broken
+    /// price feed
+    /// Follows the same quorum and timestamp rules as `submit_outcome_multi`
+    pub fn submit_void(
+        env: Env,
+        call_id: u64,
+        timestamp: u64,
+        signatures: Vec<(BytesN<32>, BytesN<64>)>,
+    ) -> bool {
+        Self::settle_with_signatures(&env, call_id, None, 0, timestamp, &signatures);
+
+        env.events().publish(
+            (Symbol::new(&env, "call_voided"),),
+            Event::CallVoided(call_id),
+        );
+
+        true
+    }
+
+    /// Void a call that has no final outcome yet (owner or settlement arbiter)
+    /// An open dispute is closed and its bond refunded to the challenger
+    pub fn void_call(env: Env, caller: Address, call_id: u64) {
+        Self::require_role(&env, Role::SettlementArbiter, &caller);
+
+        let mut call_data = Self::load_call(&env, call_id);
+
+        if Self::is_outcome_final(&env, &call_data) {
+            panic!("Outcome already final");
+        }
+
+        if let Some(dispute) = Self::read_dispute(&env, call_id) {
+            env.storage()
+                .persistent()
+                .remove(&DataKey::Dispute(call_id));
+
+            if dispute.bond > 0 {
+                token::Client::new(&env, &call_data.token).transfer(
+                    &env.current_contract_address(),
+                    &dispute.challenger,
+                    &dispute.bond,
+                );
+            }
+        }
+
+        call_data.settled = true;
+        call_data.outcome = None;
+        call_data.final_price = None;
+        call_data.voided = true;
+        call_data.dispute_deadline = env.ledger().timestamp();
+        call_data.finalized = true;
+        Self::save_call(&env, &call_data);
+
+        env.events().publish(
+            (Symbol::new(&env, "call_voided"),),
+            Event::CallVoided(call_id),
+        );
+    }
+
+    /// Challenge a provisional outcome during the dispute period
+    /// The challenger must hold a stake in the call and escrows the dispute
+    /// bond, which must be non-zero
+    pub fn challenge_outcome(env: Env, call_id: u64, challenger: Address) {
+        Self::assert_not_paused(&env);
+        challenger.require_auth();
+
+        let call_data = Self::load_call(&env, call_id);
+
+        if !call_data.settled {
+            panic!("Call not settled");
+        }
+        if call_data.finalized || env.ledger().timestamp() > call_data.dispute_deadline {
+            panic!("Dispute period over");
+        }
+
+        if Self::read_dispute(&env, call_id).is_some() {
+            panic!("Outcome already disputed");
+        }
+
+        let registry = Self::registry_client(&env);
+        if registry.get_user_stake(&call_id, &challenger, &true) <= 0
+            && registry.get_user_stake(&call_id, &challenger, &false) <= 0
+        {
+            panic!("Only stakers can challenge");
+        }
+
+        // Configs stored before bonds were required may still hold 0
+        let bond = Self::get_dispute_config(&env).bond;
+        if bond <= 0 {
+            panic!("Dispute bond not set");
+        }
+        token::Client::new(&env, &call_data.token).transfer(
+            &challenger,
+            &env.current_contract_address(),
+            &bond,
+        );
+
+        let dispute_key = DataKey::Dispute(call_id);
+        env.storage().persistent().set(
+            &dispute_key,
+            &Dispute {
+                challenger: challenger.clone(),
+                bond,
+            },
+        );
+        Self::extend_persistent(&env, &dispute_key);
+
+        env.events().publish(
+            (Symbol::new(&env, "outcome_challenged"),),
+            Event::OutcomeChallenged(call_id, challenger, bond),
+        );
+    }
+
+    /// Finalize an unchallenged outcome once the dispute period has passed
+    pub fn finalize_outcome(env: Env, call_id: u64) {
+        let mut call_data = Self::load_call(&env, call_id);
+
+        if call_data.finalized {
+            panic!("Outcome already final");
+        }
+        if !Self::is_outcome_final(&env, &call_data) {
+            panic!("Outcome not final");
+        }
+
+        call_data.finalized = true;
+        Self::save_call(&env, &call_data);
+
+        env.events().publish(
+            (Symbol::new(&env, "outcome_finalized"),),
+            Event::OutcomeFinalized(
+                call_id,
+                call_data.outcome.unwrap(),
+                call_data.final_price.unwrap(),
+            ),
+        );
+    }
+
+    /// Resolve a disputed outcome by confirming or overriding it
+    /// (owner or settlement arbiter)
+    /// The bond is refunded if the outcome or price changes, otherwise it is
+    /// slashed to the treasury
+    pub fn resolve_dispute(
+        env: Env,
+        caller: Address,
+        call_id: u64,
+        outcome: bool,
+        final_price: u128,
+    ) {
+        Self::require_role(&env, Role::SettlementArbiter, &caller);
+
+        let dispute =
+            Self::read_dispute(&env, call_id).unwrap_or_else(|| panic!("Outcome not disputed"));
+        let mut call_data = Self::load_call(&env, call_id);
+
+        let upheld =
+            call_data.outcome != Some(outcome) || call_data.final_price != Some(final_price);
+
+        call_data.outcome = Some(outcome);
+        call_data.final_price = Some(final_price);
+        call_data.finalized = true;
+        Self::save_call(&env, &call_data);
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Dispute(call_id));
+
+        if dispute.bond > 0 {
+            let bond_recipient = if upheld {
+                dispute.challenger
+            } else {
+                Self::get_fee_config(&env).treasury
+            };
+            token::Client::new(&env, &call_data.token).transfer(
+                &env.current_contract_address(),
+                &bond_recipient,
+                &dispute.bond,
+            );
+        }
+
+        env.events().publish(
+            (Symbol::new(&env, "dispute_resolved"),),
+            Event::DisputeResolved(call_id, outcome, final_price, upheld),
+        );
+    }
+
+    /// Get the open dispute for a call (view function)
+    pub fn get_dispute(env: Env, call_id: u64) -> Option<Dispute> {
+        Self::read_dispute(&env, call_id)
+    }
+
+    /// Register a call (CallRegistry only)
+    /// The registry transfers the initial stake to this contract before registering
+    /// Rejects ids that are already registered
+    /// Snapshots the current fee configuration for the call's payouts
+    pub fn register_call(
+        env: Env,
+        call_id: u64,
+        token: Address,
+        long_tokens: u128,
+        short_tokens: u128,
+        end_ts: u64,
+    ) {
+        Self::require_registry_auth(&env);
+
+        if env.storage().persistent().has(&DataKey::Call(call_id)) {
+            panic!("Call already registered");
+        }
+
+        Self::store_new_call(&env, call_id, token, long_tokens, short_tokens, end_ts);
+    }
+
+    /// Credit a version 1 call with the stakes the registry held for it
+    /// (CallRegistry only)
+    /// The registry transfers the stakes to this contract during its own
+    /// migration, which must run after this contract's migration
+    /// The registry's totals replace the stakes recorded here, and calls this
+    /// contract never recorded are registered with them
+    pub fn fund_migrated_call(
+        env: Env,
+        call_id: u64,
+        token: Address,
+        long_tokens: u128,
+        short_tokens: u128,
+        end_ts: u64,
+    ) {
+        Self::require_registry_auth(&env);
+
+        if Self::read_schema_version(&env) < CURRENT_SCHEMA_VERSION {
+            panic!("Migration pending");
+        }
+
+        let stored: Option<CallData> = env.storage().persistent().get(&DataKey::Call(call_id));
+        let Some(mut call_data) = stored else {
+            Self::store_new_call(&env, call_id, token, long_tokens, short_tokens, end_ts);
+            return;
+        };
+        if call_data.token != token {
+            panic!("Token mismatch");
+        }
+        // Version 1 records were registered by anyone, so the registry's
+        // totals replace them
+        call_data.long_tokens = long_tokens;
+        call_data.short_tokens = short_tokens;
+        call_data.escrow += long_tokens + short_tokens;
+        // Winners get the full sweep delay to claim the stakes arriving now
+        if call_data.settled {
+            call_data.dispute_deadline = call_data.dispute_deadline.max(env.ledger().timestamp());
+        }
+        Self::save_call(&env, &call_data);
+    }
+
+    /// Record a stake added to a registered call (CallRegistry only)
+    /// The registry transfers the stake to this contract before recording it
+    /// Keeps long_tokens/short_tokens in sync with the registry totals
+    pub fn record_stake(env: Env, call_id: u64, position: bool, amount: u128) {
+        Self::require_registry_auth(&env);
+
+        let mut call_data = Self::load_call(&env, call_id);
+
+        if call_data.settled {
+            panic!("Call already settled");
+        }
+
+        if position {
+            call_data.long_tokens += amount;
+        } else {
+            call_data.short_tokens += amount;
+        }
+        call_data.escrow += amount;
+
+        Self::save_call(&env, &call_data);
+    }
+
+    /// Set the share of the losing pool paid to a call's creator if the call
+    /// resolves YES (CallRegistry only)
+    /// The registry sets it when creating the call, within its admin's cap
+    pub fn set_creator_fee(env: Env, call_id: u64, creator: Address, basis_points: u32) {
+        Self::require_registry_auth(&env);
+
+        if basis_points > 10_000 {
+            panic!("Fee basis points cannot exceed 10000");
+        }
+        if Self::load_call(&env, call_id).settled {
+            panic!("Call already settled");
+        }
+
+        let key = DataKey::CreatorFee(call_id);
+        env.storage().persistent().set(
+            &key,
+            &CreatorFee {
+                creator,
+                basis_points,
+            },
+        );
+        Self::extend_persistent(&env, &key);
+    }
+
+    /// Get a call's creator fee (view function)
+    pub fn get_creator_fee(env: Env, call_id: u64) -> Option<CreatorFee> {
+        Self::read_creator_fee(&env, call_id)
+    }
+
+    /// Refund the creator of a cancelled call (CallRegistry only)
+    /// Only a call whose sole stake is the creator's can be cancelled
+    /// Closes the call so it can no longer be settled or paid out again
+    pub fn cancel_call(env: Env, call_id: u64, creator: Address, amount: u128) {
+        Self::require_registry_auth(&env);
+
+        let mut call_data = Self::load_call(&env, call_id);
+
+        if call_data.settled {
+            panic!("Call already settled");
+        }
+        if call_data.short_tokens != 0 || call_data.long_tokens != amount {
+            panic!("Call has other stakers");
+        }
+        // An unopposed call may already have refunded the creator after end_ts
+        if Self::is_withdrawn(&env, call_id, &creator) {
+            panic!("Already withdrawn");
+        }
+
+        call_data.escrow = call_data
+            .escrow
+            .checked_sub(amount)
+            .expect("Insufficient escrow");
+        call_data.settled = true;
+        call_data.finalized = true;
+        call_data.cancelled = true;
+        Self::save_call(&env, &call_data);
+
+        // The creator's refund counts as their withdrawal
+        Self::mark_withdrawn(&env, call_id, &creator);
+
+        let token_client = token::Client::new(&env, &call_data.token);
+        token_client.transfer(
+            &env.current_contract_address(),
+            &creator,
+            &Self::to_i128(amount),
+        );
+
+        env.events().publish(
+            (Symbol::new(&env, "stake_refunded"),),
+            Event::StakeRefunded(call_id, creator, amount),
+        );
+    }
+
+    /// Withdraw payout for a settled call
+    /// The user's stakes are read from the CallRegistry, so the payout cannot
+    /// be inflated by the caller
+    pub fn withdraw_payout(env: Env, call_id: u64, user: Address) -> u128 {
+        user.require_auth();
+        Self::pay_out_registry_stakes(&env, call_id, user)
+    }
+
+    /// Send a user's payout for a settled call to the user's own address
+    /// Permissionless so a keeper can distribute payouts after settlement;
+    /// the caller never receives any funds
+    pub fn push_payout(env: Env, call_id: u64, user: Address) -> u128 {
+        Self::pay_out_registry_stakes(&env, call_id, user)
+    }
+
+    /// Withdraw payouts for several calls in one transaction
+    /// Calls the user already withdrew from, or that are not yet claimable,
+    /// are skipped rather than failing the batch
+    /// Payouts are aggregated into one transfer per stake token
+    /// Returns one result per call id, in order
+    pub fn claim_many(env: Env, user: Address, call_ids: Vec<u64>) -> Vec<ClaimResult> {
+        user.require_auth();
+
+        let registry = Self::registry_client(&env);
+        let mut results: Vec<ClaimResult> = Vec::new(&env);
+        let mut payouts: Map<Address, i128> = Map::new(&env);
+
+        for call_id in call_ids.iter() {
+            if Self::is_withdrawn(&env, call_id, &user) {
+                results.push_back(ClaimResult::AlreadyClaimed);
+                continue;
+            }
+            if !Self::is_claimable(&env, &Self::load_call(&env, call_id)) {
+                results.push_back(ClaimResult::NotSettled);
+                continue;
+            }
+
+            let stake_yes = registry.get_user_stake(&call_id, &user, &true);
+            let stake_no = registry.get_user_stake(&call_id, &user, &false);
+            let payout = Self::record_payout(&env, call_id, &user, stake_yes, stake_no);
+            Self::publish_payout(&env, call_id, &user, &payout);
+
+            let net = payouts.get(payout.token.clone()).unwrap_or(0);
+            payouts.set(payout.token, net + payout.net);
+            results.push_back(ClaimResult::Paid(Self::to_u128(payout.net)));
+        }
+
+        let contract = env.current_contract_address();
+        for (token, net) in payouts.iter() {
+            if net > 0 {
+                token::Client::new(&env, &token).transfer(&contract, &user, &net);
+            }
+        }
+
+        results
+    }
+
+    /// Pay out a claim made through the CallRegistry (CallRegistry only)
+    /// The registry passes the user's stakes and clears them afterwards; this
+    /// never calls back into the registry
+    pub fn claim_payout(
+        env: Env,
+        call_id: u64,
+        user: Address,
+        stake_yes: i128,
+        stake_no: i128,
+    ) -> u128 {
+        Self::require_registry_auth(&env);
+
+        Self::pay_out(&env, call_id, user, stake_yes, stake_no)
+    }
+
+    /// Get the lifecycle status of a call (view function)
+    pub fn call_status(env: Env, call_id: u64) -> CallStatus {
+        let call_data = Self::load_call(&env, call_id);
+
+        if call_data.cancelled {
+            CallStatus::Cancelled
+        } else if call_data.voided {
+            CallStatus::Voided
+        } else if env.ledger().timestamp() < call_data.end_ts {
+            CallStatus::Open
+        } else if Self::is_unopposed(&call_data) {
+            CallStatus::Unopposed
+        } else if !call_data.settled {
+            CallStatus::AwaitingOutcome
+        } else if Self::read_dispute(&env, call_id).is_some() {
+            CallStatus::Disputed
+        } else if Self::is_outcome_final(&env, &call_data) {
+            CallStatus::Final
+        } else {
+            CallStatus::Provisional
+        }
+    }
+
+    /// Get call data (view function)
+    pub fn get_call(env: Env, call_id: u64) -> Option<CallData> {
+        let key = DataKey::Call(call_id);
+        let call_data = env.storage().persistent().get(&key);
+        if call_data.is_some() {
+            Self::extend_persistent(&env, &key);
+        }
+        call_data
+    }
+
+    /// Extend the TTL of a call, its fees and its open dispute
+    /// Anyone can pay to keep a call from being archived
+    pub fn bump_call(env: Env, call_id: u64) {
+        Self::load_call(&env, call_id);
+
+        for key in [
+            DataKey::CallFee(call_id),
+            DataKey::CreatorFee(call_id),
+            DataKey::PayoutTotals(call_id),
+            DataKey::Dispute(call_id),
+        ] {
+            if env.storage().persistent().has(&key) {
+                Self::extend_persistent(&env, &key);
+            }
+        }
+    }
+
+    /// Check if user already withdrew from a call
+    pub fn has_withdrawn(env: Env, call_id: u64, user: Address) -> bool {
+        Self::is_withdrawn(&env, call_id, &user)
+    }
+
+    /// Get what a call has paid out of its pool so far (view function)
+    pub fn get_payout_totals(env: Env, call_id: u64) -> PayoutTotals {
+        Self::read_payout_totals(&env, call_id)
+    }
+
+    /// Move the escrow a resolved call has left after its payouts to the
+    /// treasury
+    /// Pro-rata payouts round down, so a few units stay behind once every
+    /// winner has withdrawn; anything still unclaimed can also be swept once
+    /// the sweep delay after the dispute period has passed
+    /// Anyone can trigger the sweep; funds only go to the treasury
+    pub fn sweep_dust(env: Env, call_id: u64) -> u128 {
+        let mut call_data = Self::load_call(&env, call_id);
+
+        if !call_data.settled || !Self::is_outcome_final(&env, &call_data) {
+            panic!("Outcome not final");
+        }
+        let outcome = match call_data.outcome {
+            Some(outcome) if !Self::is_unopposed(&call_data) => outcome,
+            _ => panic!("Refunded calls leave no dust"),
+        };
+
+        let winning_tokens = if outcome {
+            call_data.long_tokens
+        } else {
+            call_data.short_tokens
+        };
+        let all_claimed = Self::read_payout_totals(&env, call_id).claimed_stake >= winning_tokens;
+        let deadline_passed =
+            env.ledger().timestamp() >= call_data.dispute_deadline + DUST_SWEEP_DELAY;
+        if !all_claimed && !deadline_passed {
+            panic!("Claims outstanding");
+        }
+
+        let dust = call_data.escrow;
+        if dust == 0 {
+            panic!("No dust to sweep");
+        }
+        call_data.finalized = true;
+        call_data.escrow = 0;
+        Self::save_call(&env, &call_data);
+
+        let mut totals = Self::read_payout_totals(&env, call_id);
+        totals.paid += dust;
+        Self::write_payout_totals(&env, call_id, &totals);
+
+        let treasury = Self::get_fee_config(&env).treasury;
+        token::Client::new(&env, &call_data.token).transfer(
+            &env.current_contract_address(),
+            &treasury,
+            &Self::to_i128(dust),
+        );
+
+        env.events().publish(
+            (Symbol::new(&env, "dust_swept"),),
+            Event::DustSwept(call_id, treasury, dust),
+        );
+
+        dust
+    }
+
+    /// Set the TTL threshold and extension applied to stored entries (owner only)
+    pub fn set_ttl_config(env: Env, threshold: u32, extend_to: u32) {
+        Self::require_owner_auth(&env);
+
+        if threshold == 0 || threshold >= extend_to {
+            panic!("Threshold must be > 0 and below extend_to");
+        }
+        if extend_to > env.storage().max_ttl() {
+            panic!("extend_to exceeds max TTL");
+        }
+
+        env.storage().instance().set(
+            &TTL_CONFIG,
+            &TtlConfig {
+                threshold,
+                extend_to,
+            },
+        );
+    }
+
+    pub fn get_ttl_config_view(env: Env) -> TtlConfig {
+        Self::get_ttl_config(&env)
+    }
+
+    /// Replace the contract code, keeping its storage and balances (owner only)
+    /// Run `migrate` afterwards if the new code raises the schema version
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
+        Self::require_owner_auth(&env);
+
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+
+        env.events().publish(
+            (Symbol::new(&env, "contract_upgraded"),),
+            Event::ContractUpgraded(new_wasm_hash),
+        );
+    }
+
+    /// Bring stored data up to `CURRENT_SCHEMA_VERSION` (owner only)
+    /// Moves at most `limit` legacy entries per invocation so large
+    /// deployments can migrate over several transactions
+    /// Returns the stored schema version after this batch
+    pub fn migrate(env: Env, limit: u32) -> u32 {
+        Self::require_owner_auth(&env);
+
+        if limit == 0 {
+            panic!("Limit must be > 0");
+        }
+        let version = Self::read_schema_version(&env);
+        if version >= CURRENT_SCHEMA_VERSION {
+            panic!("Schema already current");
+        }
+
+        // 1 -> 2: move calls and withdrawal flags out of the instance maps.
+        // Version 1 stakes were held by the registry, so migrated calls start
+        // without escrow until the registry's migration forwards them through
+        // `fund_migrated_call`. A settled version 1 outcome was already final;
+        // its dispute deadline becomes the migration time so the dust sweep
+        // delay runs from here.
+        let instance = env.storage().instance();
+        let mut remaining = limit;
+
+        let now = env.ledger().timestamp();
+        let mut calls: Map<u64, CallDataV1> =
+            instance.get(&CALLS).unwrap_or_else(|| Map::new(&env));
+        for call_id in calls.keys() {
+            if remaining == 0 {
+                break;
+            }
+            let legacy = calls.get_unchecked(call_id);
+            Self::save_call(
+                &env,
+                &CallData {
+                    id: legacy.id,
+                    token: legacy.token,
+                    long_tokens: legacy.long_tokens,
+                    short_tokens: legacy.short_tokens,
+                    end_ts: legacy.end_ts,
+                    settled: legacy.settled,
+                    outcome: legacy.outcome,
+                    final_price: legacy.final_price,
+                    escrow: 0,
+                    dispute_deadline: if legacy.settled { now } else { 0 },
+                    finalized: legacy.settled,
+                    voided: false,
+                    cancelled: false,
+                },
+            );
+            calls.remove(call_id);
+            remaining -= 1;
+        }
+
+        let mut withdrawals: Map<(u64, Address), bool> =
+            instance.get(&WITHDRAWALS).unwrap_or_else(|| Map::new(&env));
+        for (call_id, user) in withdrawals.keys() {
+            if remaining == 0 {
+                break;
+            }
+            if withdrawals.get_unchecked((call_id, user.clone())) {
+                Self::mark_withdrawn(&env, call_id, &user);
+            }
+            withdrawals.remove((call_id, user));
+            remaining -= 1;
+        }
+
+        if !calls.is_empty() || !withdrawals.is_empty() {
+            instance.set(&CALLS, &calls);
+            instance.set(&WITHDRAWALS, &withdrawals);
+            return version;
+        }
+
+        instance.remove(&CALLS);
+        instance.remove(&WITHDRAWALS);
+
+        // Existing fees keep being charged on the gross payout
+        if let Some(legacy) = env
+            .storage()
+            .persistent()
+            .get::<_, FeeConfigV1>(&FEE_CONFIG)
+        {
+            env.storage().persistent().set(
+                &FEE_CONFIG,
+                &FeeConfig {
+                    basis_points: legacy.basis_points,
+                    treasury: legacy.treasury,
+                    mode: FeeMode::Gross,
+                },
+            );
+        }
+        instance.set(&SCHEMA_VERSION, &CURRENT_SCHEMA_VERSION);
+
+        env.events().publish(
+            (Symbol::new(&env, "schema_migrated"),),
+            Event::SchemaMigrated(CURRENT_SCHEMA_VERSION),
+        );
+
+        CURRENT_SCHEMA_VERSION
+    }
 
-        withdrawals.get((call_id, user)).unwrap_or(false)
+    pub fn get_schema_version(env: Env) -> u32 {
+        Self::read_schema_version(&env)
     }
 }
 
@@ -0,0 +1,7 @@
+use soroban_sdk::{contractclient, Address, Env};
+
+/// Subset of the CallRegistry interface used by the outcome manager
+#[contractclient(name = "CallRegistryClient")]
+pub trait CallRegistryInterface {
+    fn get_user_stake(env: Env, call_id: u64, user: Address, position: bool) -> i128;
+}
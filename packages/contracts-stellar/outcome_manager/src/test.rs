@@ -1,154 +1,2250 @@
 #![cfg(test)]
+extern crate std;
 
-use crate::{CallData, OutcomeManagerContract, OutcomeManagerContractClient, CALLS};
+use crate::{
+    legacy::{CallDataV1, FeeConfigV1},
+    CallData, CallStatus, ClaimResult, DataKey, DisputeConfig, FeeMode, OutcomeManagerContract,
+    OutcomeManagerContractClient, Role, CALLS, CURRENT_SCHEMA_VERSION, DEFAULT_DISPUTE_BOND,
+    DISPUTE_CONFIG, DUST_SWEEP_DELAY, FEE_CONFIG, SCHEMA_VERSION, WITHDRAWALS,
+};
+use call_registry::{CallRegistry, CallRegistryClient, CreateCallMetadata};
+use ed25519_dalek::{Signer, SigningKey};
 use soroban_sdk::{
-    testutils::{Address as _, MockAuth, MockAuthInvoke},
-    token, Address, BytesN, Env, IntoVal,
+    testutils::{
+        storage::{Instance as _, Persistent as _},
+        Address as _, Events, Ledger, MockAuth, MockAuthInvoke,
+    },
+    token, vec, Address, Bytes, BytesN, Env, IntoVal, Map, String, Symbol,
 };
 
+fn setup_with_registry<'a>(
+    env: &'a Env,
+    owner: &Address,
+) -> (OutcomeManagerContractClient<'a>, CallRegistryClient<'a>) {
+    let contract_id = env.register_contract(None, OutcomeManagerContract);
+    let client = OutcomeManagerContractClient::new(env, &contract_id);
+    let registry_id = env.register_contract(None, CallRegistry);
+    let registry = CallRegistryClient::new(env, &registry_id);
+
+    registry.initialize(owner);
+    client.initialize(owner, &registry_id);
+    registry.set_outcome_manager(&contract_id);
+
+    (client, registry)
+}
+
+fn oracle_key(env: &Env, seed: u8) -> (SigningKey, BytesN<32>) {
+    let signing_key = SigningKey::from_bytes(&[seed; 32]);
+    let pubkey = BytesN::from_array(env, &signing_key.verifying_key().to_bytes());
+    (signing_key, pubkey)
+}
+
+fn sign_bytes(env: &Env, signing_key: &SigningKey, message: &Bytes) -> BytesN<64> {
+    let message: std::vec::Vec<u8> = message.iter().collect();
+    BytesN::from_array(env, &signing_key.sign(&message).to_bytes())
+}
+
+fn sign_outcome(
+    env: &Env,
+    client: &OutcomeManagerContractClient,
+    signing_key: &SigningKey,
+    call_id: u64,
+    outcome: bool,
+    final_price: u128,
+    timestamp: u64,
+) -> BytesN<64> {
+    let message = client.outcome_message(&call_id, &outcome, &final_price, &timestamp);
+    sign_bytes(env, signing_key, &message)
+}
+
+fn setup_quorum<'a>(
+    env: &'a Env,
+    threshold: u32,
+) -> (
+    OutcomeManagerContractClient<'a>,
+    [(SigningKey, BytesN<32>); 3],
+) {
+    let contract_id = env.register_contract(None, OutcomeManagerContract);
+    let client = OutcomeManagerContractClient::new(env, &contract_id);
+
+    let owner = Address::generate(env);
+    let registry = Address::generate(env);
+    let token = Address::generate(env);
+    let oracles = [oracle_key(env, 4), oracle_key(env, 5), oracle_key(env, 6)];
+
+    client.initialize(&owner, &registry);
+    for (_, oracle) in oracles.iter() {
+        client.set_oracle(&owner, oracle, &true);
+    }
+    client.set_oracle_threshold(&owner, &threshold);
+    client.register_call(&1u64, &token, &1000u128, &500u128, &1000000u64);
+    env.ledger().set_timestamp(1000020);
+
+    (client, oracles)
+}
+
+fn create_stake_token(env: &Env) -> Address {
+    let stake_token_admin = Address::generate(env);
+    env.register_stellar_asset_contract_v2(stake_token_admin)
+        .address()
+}
+
+/// Create a registry call whose creator takes `creator_fee_bps` of the
+/// losing pool if it resolves YES
+fn create_registry_call(
+    env: &Env,
+    registry: &CallRegistryClient,
+    creator: &Address,
+    stake_token: &Address,
+    stake_amount: i128,
+    end_ts: u64,
+    creator_fee_bps: u32,
+) -> u64 {
+    token::StellarAssetClient::new(env, stake_token).mint(creator, &stake_amount);
+    registry.set_stake_token(stake_token, &1, &i128::MAX);
+    if creator_fee_bps > 0 {
+        registry.set_max_creator_fee(&creator_fee_bps);
+    }
+
+    let metadata = CreateCallMetadata {
+        token_address: Address::generate(env),
+        pair_id: BytesN::from_array(env, &[0; 32]),
+        ipfs_cid: String::from_str(env, "QmHash"),
+    };
+    registry.create_call(
+        creator,
+        stake_token,
+        &stake_amount,
+        &end_ts,
+        &metadata,
+        &creator_fee_bps,
+    )
+}
+
+fn stake_on_registry_call(
+    env: &Env,
+    registry: &CallRegistryClient,
+    call_id: u64,
+    staker: &Address,
+    amount: i128,
+    position: bool,
+) {
+    let stake_token = registry.get_call(&call_id).stake_token;
+    token::StellarAssetClient::new(env, &stake_token).mint(staker, &amount);
+    registry.stake_on_call(&call_id, staker, &amount, &position);
+}
+
+/// Create a registry call with a YES creator and a NO opponent, then settle
+/// it through a signed oracle submission
+fn settle_disputable_call(
+    env: &Env,
+    client: &OutcomeManagerContractClient,
+    registry: &CallRegistryClient,
+    creator: &Address,
+    opponent: &Address,
+    outcome: bool,
+) -> u64 {
+    let stake_token = create_stake_token(env);
+    let (signing_key, oracle) = oracle_key(env, 4);
+    client.set_oracle(&client.get_owner(), &oracle, &true);
+
+    let end_ts = env.ledger().timestamp() + 1000;
+    let call_id = create_registry_call(env, registry, creator, &stake_token, 300, end_ts, 0);
+    stake_on_registry_call(env, registry, call_id, opponent, 200, false);
+    token::StellarAssetClient::new(env, &stake_token).mint(opponent, &1000);
+
+    env.ledger().set_timestamp(end_ts + 10);
+    let signature = sign_outcome(env, client, &signing_key, call_id, outcome, 105, end_ts);
+    client.submit_outcome(&call_id, &outcome, &105u128, &end_ts, &oracle, &signature);
+
+    call_id
+}
+
+fn force_settle(env: &Env, contract_id: &Address, call_id: u64, outcome: bool) {
+    env.as_contract(contract_id, || {
+        let key = DataKey::Call(call_id);
+        let mut call_data: CallData = env.storage().persistent().get(&key).unwrap();
+        call_data.settled = true;
+        call_data.outcome = Some(outcome);
+        call_data.final_price = Some(105u128);
+        call_data.finalized = true;
+        env.storage().persistent().set(&key, &call_data);
+    });
+}
+
+/// Smallest module the host accepts as contract code: a wasm header and the
+/// env meta section declaring the interface version
+fn minimal_wasm(env: &Env) -> Bytes {
+    let name = b"contractenvmetav0";
+    let mut meta = std::vec![0u8; 4];
+    meta.extend_from_slice(&(21u64 << 32).to_be_bytes());
+
+    let mut wasm = std::vec![0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+    wasm.push(0);
+    wasm.push((1 + name.len() + meta.len()) as u8);
+    wasm.push(name.len() as u8);
+    wasm.extend_from_slice(name);
+    wasm.extend_from_slice(&meta);
+    Bytes::from_slice(env, &wasm)
+}
+
+#[test]
+fn test_initialize() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, OutcomeManagerContract);
+    let client = OutcomeManagerContractClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let registry = Address::generate(&env);
+
+    client.initialize(&owner, &registry);
+
+    // Verify oracle returns false for non-existent oracle
+    let random_oracle = BytesN::from_array(&env, &[1; 32]);
+    assert!(!client.is_authorized_oracle(&random_oracle));
+    assert!(!client.get_is_paused());
+
+    let fee_config = client.get_fee_config_view();
+    assert_eq!(fee_config.basis_points, 0);
+    assert_eq!(fee_config.treasury, owner);
+}
+
+#[test]
+fn test_set_oracle() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, OutcomeManagerContract);
+    let client = OutcomeManagerContractClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let registry = Address::generate(&env);
+    let oracle = BytesN::from_array(&env, &[2; 32]);
+
+    env.mock_all_auths();
+    client.initialize(&owner, &registry);
+
+    // Set oracle as authorized
+    client.set_oracle(&owner, &oracle, &true);
+
+    // Verify oracle is authorized
+    assert!(client.is_authorized_oracle(&oracle));
+
+    // Revoke oracle
+    client.set_oracle(&owner, &oracle, &false);
+    assert!(!client.is_authorized_oracle(&oracle));
+}
+
+#[test]
+fn test_register_call() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, OutcomeManagerContract);
+    let client = OutcomeManagerContractClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let registry = Address::generate(&env);
+    let token = Address::generate(&env);
+
+    client.initialize(&owner, &registry);
+
+    let call_id = 1u64;
+    let long_tokens = 1000u128;
+    let short_tokens = 500u128;
+    let end_ts = 1000000u64;
+
+    client.register_call(&call_id, &token, &long_tokens, &short_tokens, &end_ts);
+
+    // Verify call was registered
+    let call = client.get_call(&call_id);
+    assert!(call.is_some());
+
+    let call_data = call.unwrap();
+    assert_eq!(call_data.id, call_id);
+    assert_eq!(call_data.long_tokens, long_tokens);
+    assert_eq!(call_data.short_tokens, short_tokens);
+    assert!(!call_data.settled);
+}
+
+#[test]
+#[should_panic(expected = "Call already registered")]
+fn test_register_call_twice() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, OutcomeManagerContract);
+    let client = OutcomeManagerContractClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let registry = Address::generate(&env);
+    let token = Address::generate(&env);
+
+    client.initialize(&owner, &registry);
+
+    client.register_call(&1u64, &token, &1000u128, &500u128, &1000000u64);
+    client.register_call(&1u64, &token, &1u128, &0u128, &1000000u64);
+}
+
+#[test]
+#[should_panic]
+fn test_register_call_requires_registry_auth() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, OutcomeManagerContract);
+    let client = OutcomeManagerContractClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let registry = Address::generate(&env);
+    let attacker = Address::generate(&env);
+    let token = Address::generate(&env);
+
+    client.initialize(&owner, &registry);
+
+    env.mock_auths(&[MockAuth {
+        address: &attacker,
+        invoke: &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "register_call",
+            args: (1u64, token.clone(), 1000u128, 500u128, 1000000u64).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    client.register_call(&1u64, &token, &1000u128, &500u128, &1000000u64);
+}
+
+#[test]
+fn test_registry_registers_and_syncs_calls() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let staker = Address::generate(&env);
+    let stake_token = create_stake_token(&env);
+    let (client, registry) = setup_with_registry(&env, &owner);
+
+    let end_ts = env.ledger().timestamp() + 1000;
+    let call_id = create_registry_call(&env, &registry, &creator, &stake_token, 300, end_ts, 0);
+
+    let call_data = client.get_call(&call_id).unwrap();
+    assert_eq!(call_data.token, stake_token);
+    assert_eq!(call_data.long_tokens, 300u128);
+    assert_eq!(call_data.short_tokens, 0u128);
+    assert_eq!(call_data.end_ts, end_ts);
+
+    stake_on_registry_call(&env, &registry, call_id, &staker, 120, false);
+    stake_on_registry_call(&env, &registry, call_id, &staker, 30, true);
+
+    let call_data = client.get_call(&call_id).unwrap();
+    assert_eq!(call_data.long_tokens, 330u128);
+    assert_eq!(call_data.short_tokens, 120u128);
+}
+
+#[test]
+fn test_submit_outcome_success() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, OutcomeManagerContract);
+    let client = OutcomeManagerContractClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let registry = Address::generate(&env);
+    let token = Address::generate(&env);
+    let (signing_key, oracle) = oracle_key(&env, 4);
+
+    env.mock_all_auths();
+    client.initialize(&owner, &registry);
+
+    // Authorize oracle
+    client.set_oracle(&owner, &oracle, &true);
+
+    // Register a call
+    let call_id = 1u64;
+    client.register_call(&call_id, &token, &1000u128, &500u128, &1000000u64);
+
+    env.ledger().set_timestamp(1000020);
+    let signature = sign_outcome(&env, &client, &signing_key, call_id, true, 105, 1000010);
+    assert!(client.submit_outcome(&call_id, &true, &105u128, &1000010u64, &oracle, &signature));
+
+    let call_data = client.get_call(&call_id).unwrap();
+    assert!(call_data.settled);
+    assert_eq!(call_data.outcome, Some(true));
+    assert_eq!(call_data.final_price, Some(105u128));
+}
+
+#[test]
+fn test_outcome_message_is_domain_separated() {
+    let env = Env::default();
+    let first_id = env.register_contract(None, OutcomeManagerContract);
+    let first = OutcomeManagerContractClient::new(&env, &first_id);
+    let second_id = env.register_contract(None, OutcomeManagerContract);
+    let second = OutcomeManagerContractClient::new(&env, &second_id);
+
+    let message = first.outcome_message(&7u64, &true, &105u128, &1000010u64);
+
+    // version + domain tag + network id + contract address + payload
+    assert_eq!(message.len(), 1 + 14 + 32 + 40 + 8 + 1 + 16 + 8);
+    assert_eq!(message.get(0), Some(1u8));
+    assert_eq!(
+        message.slice(1..15),
+        Bytes::from_slice(&env, b"BACKIT_OUTCOME")
+    );
+    assert_eq!(
+        message.slice(15..47),
+        Bytes::from(env.ledger().network_id())
+    );
+
+    // The same outcome produces different bytes on another deployment
+    assert_ne!(
+        message,
+        second.outcome_message(&7u64, &true, &105u128, &1000010u64)
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Crypto, InvalidInput)")]
+fn test_submit_outcome_rejects_signature_for_other_deployment() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let first_id = env.register_contract(None, OutcomeManagerContract);
+    let first = OutcomeManagerContractClient::new(&env, &first_id);
+    let second_id = env.register_contract(None, OutcomeManagerContract);
+    let second = OutcomeManagerContractClient::new(&env, &second_id);
+
+    let owner = Address::generate(&env);
+    let registry = Address::generate(&env);
+    let token = Address::generate(&env);
+    let (signing_key, oracle) = oracle_key(&env, 4);
+
+    for client in [&first, &second] {
+        client.initialize(&owner, &registry);
+        client.set_oracle(&owner, &oracle, &true);
+        client.register_call(&7u64, &token, &1000u128, &500u128, &1000000u64);
+    }
+
+    env.ledger().set_timestamp(1000020);
+
+    // A valid signature for the first deployment is replayed on the second
+    let signature = sign_outcome(&env, &first, &signing_key, 7, true, 105, 1000010);
+    second.submit_outcome(&7u64, &true, &105u128, &1000010u64, &oracle, &signature);
+}
+
+#[test]
+#[should_panic(expected = "Error(Crypto, InvalidInput)")]
+fn test_submit_outcome_rejects_legacy_message() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, OutcomeManagerContract);
+    let client = OutcomeManagerContractClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let registry = Address::generate(&env);
+    let token = Address::generate(&env);
+    let (signing_key, oracle) = oracle_key(&env, 4);
+
+    client.initialize(&owner, &registry);
+    client.set_oracle(&owner, &oracle, &true);
+    client.register_call(&7u64, &token, &1000u128, &500u128, &1000000u64);
+
+    env.ledger().set_timestamp(1000020);
+
+    // call_id || outcome || final_price || timestamp without domain separation
+    let mut legacy = Bytes::new(&env);
+    legacy.extend_from_array(&7u64.to_be_bytes());
+    legacy.push_back(1);
+    legacy.extend_from_array(&105u128.to_be_bytes());
+    legacy.extend_from_array(&1000010u64.to_be_bytes());
+    let signature = sign_bytes(&env, &signing_key, &legacy);
+
+    client.submit_outcome(&7u64, &true, &105u128, &1000010u64, &oracle, &signature);
+}
+
+#[test]
+#[should_panic(expected = "Call not ended")]
+fn test_submit_outcome_before_end() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, OutcomeManagerContract);
+    let client = OutcomeManagerContractClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let registry = Address::generate(&env);
+    let token = Address::generate(&env);
+    let (signing_key, oracle) = oracle_key(&env, 4);
+
+    client.initialize(&owner, &registry);
+    client.set_oracle(&owner, &oracle, &true);
+    client.register_call(&1u64, &token, &1000u128, &500u128, &1000000u64);
+
+    env.ledger().set_timestamp(999_990);
+    let signature = sign_outcome(&env, &client, &signing_key, 1, true, 105, 999_990);
+    client.submit_outcome(&1u64, &true, &105u128, &999_990u64, &oracle, &signature);
+}
+
+#[test]
+#[should_panic(expected = "Observation before call end")]
+fn test_submit_outcome_observation_before_end() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, OutcomeManagerContract);
+    let client = OutcomeManagerContractClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let registry = Address::generate(&env);
+    let token = Address::generate(&env);
+    let (signing_key, oracle) = oracle_key(&env, 4);
+
+    client.initialize(&owner, &registry);
+    client.set_oracle(&owner, &oracle, &true);
+    client.register_call(&1u64, &token, &1000u128, &500u128, &1000000u64);
+
+    env.ledger().set_timestamp(1000020);
+    let signature = sign_outcome(&env, &client, &signing_key, 1, true, 105, 999_999);
+    client.submit_outcome(&1u64, &true, &105u128, &999_999u64, &oracle, &signature);
+}
+
+#[test]
+#[should_panic(expected = "Observation outside settlement window")]
+fn test_submit_outcome_stale_observation() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, OutcomeManagerContract);
+    let client = OutcomeManagerContractClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let registry = Address::generate(&env);
+    let token = Address::generate(&env);
+    let (signing_key, oracle) = oracle_key(&env, 4);
+
+    client.initialize(&owner, &registry);
+    client.set_oracle(&owner, &oracle, &true);
+    client.set_settlement_window(&600u64);
+    client.register_call(&1u64, &token, &1000u128, &500u128, &1000000u64);
+
+    // Observed 601 seconds after end_ts with a 600 second window
+    env.ledger().set_timestamp(1002000);
+    let signature = sign_outcome(&env, &client, &signing_key, 1, true, 105, 1000601);
+    client.submit_outcome(&1u64, &true, &105u128, &1000601u64, &oracle, &signature);
+}
+
+#[test]
+#[should_panic(expected = "Observation in the future")]
+fn test_submit_outcome_future_observation() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, OutcomeManagerContract);
+    let client = OutcomeManagerContractClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let registry = Address::generate(&env);
+    let token = Address::generate(&env);
+    let (signing_key, oracle) = oracle_key(&env, 4);
+
+    client.initialize(&owner, &registry);
+    client.set_oracle(&owner, &oracle, &true);
+    client.register_call(&1u64, &token, &1000u128, &500u128, &1000000u64);
+
+    env.ledger().set_timestamp(1000020);
+    let signature = sign_outcome(&env, &client, &signing_key, 1, true, 105, 1000030);
+    client.submit_outcome(&1u64, &true, &105u128, &1000030u64, &oracle, &signature);
+}
+
+#[test]
+fn test_set_settlement_window() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, OutcomeManagerContract);
+    let client = OutcomeManagerContractClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let registry = Address::generate(&env);
+
+    client.initialize(&owner, &registry);
+    assert_eq!(client.get_settlement_window_view(), 3_600u64);
+
+    client.set_settlement_window(&86_400u64);
+    assert_eq!(client.get_settlement_window_view(), 86_400u64);
+}
+
+#[test]
+#[should_panic]
+fn test_set_settlement_window_requires_owner_auth() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, OutcomeManagerContract);
+    let client = OutcomeManagerContractClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let registry = Address::generate(&env);
+    let attacker = Address::generate(&env);
+
+    client.initialize(&owner, &registry);
+
+    env.mock_auths(&[MockAuth {
+        address: &attacker,
+        invoke: &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "set_settlement_window",
+            args: (86_400u64,).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    client.set_settlement_window(&86_400u64);
+}
+
+#[test]
+fn test_submit_outcome_multi_reaches_quorum() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, oracles) = setup_quorum(&env, 2);
+    assert_eq!(client.get_oracle_threshold_view(), 2u32);
+
+    let signatures = vec![
+        &env,
+        (
+            oracles[0].1.clone(),
+            sign_outcome(&env, &client, &oracles[0].0, 1, false, 95, 1000010),
+        ),
+        (
+            oracles[2].1.clone(),
+            sign_outcome(&env, &client, &oracles[2].0, 1, false, 95, 1000010),
+        ),
+    ];
+    assert!(client.submit_outcome_multi(&1u64, &false, &95u128, &1000010u64, &signatures));
+
+    let call_data = client.get_call(&1u64).unwrap();
+    assert!(call_data.settled);
+    assert_eq!(call_data.outcome, Some(false));
+    assert_eq!(call_data.final_price, Some(95u128));
+}
+
+#[test]
+#[should_panic(expected = "Oracle quorum not reached")]
+fn test_submit_outcome_multi_below_quorum() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, oracles) = setup_quorum(&env, 3);
+
+    let signatures = vec![
+        &env,
+        (
+            oracles[0].1.clone(),
+            sign_outcome(&env, &client, &oracles[0].0, 1, true, 105, 1000010),
+        ),
+        (
+            oracles[1].1.clone(),
+            sign_outcome(&env, &client, &oracles[1].0, 1, true, 105, 1000010),
+        ),
+    ];
+    client.submit_outcome_multi(&1u64, &true, &105u128, &1000010u64, &signatures);
+}
+
+#[test]
+#[should_panic(expected = "Duplicate oracle signature")]
+fn test_submit_outcome_multi_rejects_duplicates() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, oracles) = setup_quorum(&env, 2);
+
+    let signature = sign_outcome(&env, &client, &oracles[0].0, 1, true, 105, 1000010);
+    let signatures = vec![
+        &env,
+        (oracles[0].1.clone(), signature.clone()),
+        (oracles[0].1.clone(), signature),
+    ];
+    client.submit_outcome_multi(&1u64, &true, &105u128, &1000010u64, &signatures);
+}
+
+#[test]
+#[should_panic(expected = "Oracle not authorized")]
+fn test_submit_outcome_multi_rejects_unauthorized_key() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, oracles) = setup_quorum(&env, 2);
+    let (outsider_key, outsider) = oracle_key(&env, 9);
+
+    let signatures = vec![
+        &env,
+        (
+            oracles[0].1.clone(),
+            sign_outcome(&env, &client, &oracles[0].0, 1, true, 105, 1000010),
+        ),
+        (
+            outsider,
+            sign_outcome(&env, &client, &outsider_key, 1, true, 105, 1000010),
+        ),
+    ];
+    client.submit_outcome_multi(&1u64, &true, &105u128, &1000010u64, &signatures);
+}
+
+#[test]
+#[should_panic(expected = "Error(Crypto, InvalidInput)")]
+fn test_submit_outcome_multi_rejects_conflicting_price() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, oracles) = setup_quorum(&env, 2);
+
+    // The second oracle signed a different final price
+    let signatures = vec![
+        &env,
+        (
+            oracles[0].1.clone(),
+            sign_outcome(&env, &client, &oracles[0].0, 1, true, 105, 1000010),
+        ),
+        (
+            oracles[1].1.clone(),
+            sign_outcome(&env, &client, &oracles[1].0, 1, true, 106, 1000010),
+        ),
+    ];
+    client.submit_outcome_multi(&1u64, &true, &105u128, &1000010u64, &signatures);
+}
+
+#[test]
+#[should_panic(expected = "Oracle quorum not reached")]
+fn test_submit_outcome_single_signer_below_threshold() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, oracles) = setup_quorum(&env, 2);
+
+    let signature = sign_outcome(&env, &client, &oracles[0].0, 1, true, 105, 1000010);
+    client.submit_outcome(
+        &1u64,
+        &true,
+        &105u128,
+        &1000010u64,
+        &oracles[0].1,
+        &signature,
+    );
+}
+
+#[test]
+fn test_withdraw_payout_long_wins() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let user = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    let stake_token = create_stake_token(&env);
+    let stake_token_client = token::Client::new(&env, &stake_token);
+    let (client, registry) = setup_with_registry(&env, &owner);
+    let contract_id = client.address.clone();
+    client.set_fee_config(&owner, &500u32, &treasury, &FeeMode::Gross);
+
+    // Long side: creator 900 + user 100, short side: opponent 500
+    let end_ts = env.ledger().timestamp() + 1000;
+    let call_id = create_registry_call(&env, &registry, &creator, &stake_token, 900, end_ts, 0);
+    stake_on_registry_call(&env, &registry, call_id, &user, 100, true);
+    stake_on_registry_call(&env, &registry, call_id, &opponent, 500, false);
+
+    force_settle(&env, &contract_id, call_id, true);
+
+    // All stakes are escrowed by the outcome manager
+    assert_eq!(stake_token_client.balance(&contract_id), 1500i128);
+    assert_eq!(client.get_call(&call_id).unwrap().escrow, 1500u128);
+
+    let payout = client.withdraw_payout(&call_id, &user);
+
+    assert_eq!(payout, 143u128);
+    assert_eq!(stake_token_client.balance(&user), 143i128);
+    assert_eq!(client.accrued_fees(&stake_token), 7i128);
+    assert_eq!(stake_token_client.balance(&treasury), 0i128);
+    assert_eq!(stake_token_client.balance(&contract_id), 1357i128);
+    assert_eq!(client.get_call(&call_id).unwrap().escrow, 1350u128);
+    assert!(client.has_withdrawn(&call_id, &user));
+}
+
+#[test]
+fn test_fee_modes_charge_exact_amounts() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let user = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    let stake_token = create_stake_token(&env);
+    let stake_token_client = token::Client::new(&env, &stake_token);
+    let (client, registry) = setup_with_registry(&env, &owner);
+    let contract_id = client.address.clone();
+
+    // Long side: creator 900 + user 100, short side: opponent 500
+    let end_ts = env.ledger().timestamp() + 1000;
+    let open_call = || {
+        let call_id = create_registry_call(&env, &registry, &creator, &stake_token, 900, end_ts, 0);
+        stake_on_registry_call(&env, &registry, call_id, &user, 100, true);
+        stake_on_registry_call(&env, &registry, call_id, &opponent, 500, false);
+        call_id
+    };
+
+    client.set_fee_config(&owner, &500u32, &treasury, &FeeMode::Gross);
+    let gross_call = open_call();
+    client.set_fee_config(&owner, &500u32, &treasury, &FeeMode::Profit);
+    let profit_call = open_call();
+    force_settle(&env, &contract_id, gross_call, true);
+    force_settle(&env, &contract_id, profit_call, true);
+
+    // The user's payout is 150: their 100 stake back plus 50 of winnings
+    // Gross: 5% of 150 = 7
+    assert_eq!(client.withdraw_payout(&gross_call, &user), 143u128);
+    assert_eq!(client.accrued_fees(&stake_token), 7i128);
+
+    // Profit: 5% of 50 = 2
+    assert_eq!(client.withdraw_payout(&profit_call, &user), 148u128);
+    assert_eq!(client.accrued_fees(&stake_token), 9i128);
+    assert_eq!(stake_token_client.balance(&user), 291i128);
+}
+
+#[test]
+fn test_creator_rewarded_from_losing_pool_when_yes_wins() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let user = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    let stake_token = create_stake_token(&env);
+    let stake_token_client = token::Client::new(&env, &stake_token);
+    let (client, registry) = setup_with_registry(&env, &owner);
+    let contract_id = client.address.clone();
+
+    // YES: creator 300 + user 100, NO: opponent 400, creator fee 10%
+    let end_ts = env.ledger().timestamp() + 1000;
+    let call_id = create_registry_call(&env, &registry, &creator, &stake_token, 300, end_ts, 1_000);
+    stake_on_registry_call(&env, &registry, call_id, &user, 100, true);
+    stake_on_registry_call(&env, &registry, call_id, &opponent, 400, false);
+    force_settle(&env, &contract_id, call_id, true);
+
+    // 40 of the losing pool goes to the creator, the other 360 is shared
+    assert_eq!(client.withdraw_payout(&call_id, &creator), 610u128);
+    let last_event = env.events().all().last().unwrap();
+    let symbol: Symbol = last_event.1.get(0).unwrap().into_val(&env);
+    assert_eq!(symbol, Symbol::new(&env, "creator_rewarded"));
+
+    assert_eq!(client.withdraw_payout(&call_id, &user), 190u128);
+    assert_eq!(client.withdraw_payout(&call_id, &opponent), 0u128);
+    assert_eq!(stake_token_client.balance(&creator), 610i128);
+    assert_eq!(stake_token_client.balance(&user), 190i128);
+    assert_eq!(client.get_call(&call_id).unwrap().escrow, 0u128);
+}
+
+#[test]
+fn test_creator_not_rewarded_when_no_wins() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    let stake_token = create_stake_token(&env);
+    let (client, registry) = setup_with_registry(&env, &owner);
+    let contract_id = client.address.clone();
+
+    let end_ts = env.ledger().timestamp() + 1000;
+    let call_id = create_registry_call(&env, &registry, &creator, &stake_token, 300, end_ts, 1_000);
+    stake_on_registry_call(&env, &registry, call_id, &opponent, 400, false);
+    force_settle(&env, &contract_id, call_id, false);
+
+    assert_eq!(client.withdraw_payout(&call_id, &creator), 0u128);
+    assert_eq!(client.withdraw_payout(&call_id, &opponent), 700u128);
+    assert_eq!(client.get_call(&call_id).unwrap().escrow, 0u128);
+}
+
+#[test]
+#[should_panic]
+fn test_set_creator_fee_requires_registry_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let stake_token = create_stake_token(&env);
+    let (client, registry) = setup_with_registry(&env, &owner);
+    let contract_id = client.address.clone();
+
+    let end_ts = env.ledger().timestamp() + 1000;
+    let call_id = create_registry_call(&env, &registry, &creator, &stake_token, 300, end_ts, 0);
+
+    env.mock_auths(&[MockAuth {
+        address: &creator,
+        invoke: &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "set_creator_fee",
+            args: (call_id, creator.clone(), 10_000u32).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    client.set_creator_fee(&call_id, &creator, &10_000u32);
+}
+
+#[test]
+fn test_withdraw_accrued_fees() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let fee_manager = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    let stake_token = create_stake_token(&env);
+    let stake_token_client = token::Client::new(&env, &stake_token);
+    let (client, registry) = setup_with_registry(&env, &owner);
+    let contract_id = client.address.clone();
+    client.set_fee_config(&owner, &1_000u32, &owner, &FeeMode::Gross);
+    client.grant_role(&Role::FeeManager, &fee_manager);
+
+    let end_ts = env.ledger().timestamp() + 1000;
+    let call_id = create_registry_call(&env, &registry, &creator, &stake_token, 300, end_ts, 0);
+    stake_on_registry_call(&env, &registry, call_id, &opponent, 200, false);
+    force_settle(&env, &contract_id, call_id, true);
+
+    assert_eq!(client.withdraw_payout(&call_id, &creator), 450u128);
+    assert_eq!(client.accrued_fees(&stake_token), 50i128);
+    assert_eq!(stake_token_client.balance(&contract_id), 50i128);
+
+    client.withdraw_fees(&fee_manager, &stake_token, &recipient, &30i128);
+    assert_eq!(env.auths()[0].0, fee_manager);
+    let last_event = env.events().all().last().unwrap();
+    let symbol: Symbol = last_event.1.get(0).unwrap().into_val(&env);
+    assert_eq!(symbol, Symbol::new(&env, "fees_withdrawn"));
+
+    assert_eq!(client.accrued_fees(&stake_token), 20i128);
+    assert_eq!(stake_token_client.balance(&recipient), 30i128);
+
+    // Neither more than has accrued nor a caller without the role
+    assert!(client
+        .try_withdraw_fees(&owner, &stake_token, &recipient, &21i128)
+        .is_err());
+    assert!(client
+        .try_withdraw_fees(&recipient, &stake_token, &recipient, &20i128)
+        .is_err());
+
+    client.withdraw_fees(&owner, &stake_token, &recipient, &20i128);
+    assert_eq!(client.accrued_fees(&stake_token), 0i128);
+    assert_eq!(stake_token_client.balance(&contract_id), 0i128);
+}
+
+#[test]
+#[should_panic]
+fn test_withdraw_fees_requires_caller_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let attacker = Address::generate(&env);
+    let stake_token = create_stake_token(&env);
+    let (client, _) = setup_with_registry(&env, &owner);
+
+    env.mock_auths(&[MockAuth {
+        address: &attacker,
+        invoke: &MockAuthInvoke {
+            contract: &client.address,
+            fn_name: "withdraw_fees",
+            args: (owner.clone(), stake_token.clone(), attacker.clone(), 1i128).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    client.withdraw_fees(&owner, &stake_token, &attacker, &1i128);
+}
+
+#[test]
+fn test_pool_sums_exactly_after_dust_sweep() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let user = Address::generate(&env);
+    let other_user = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    let stake_token = create_stake_token(&env);
+    let stake_token_client = token::Client::new(&env, &stake_token);
+    let (client, registry) = setup_with_registry(&env, &owner);
+    let contract_id = client.address.clone();
+    client.set_fee_config(&owner, &0u32, &treasury, &FeeMode::Gross);
+
+    // Long side: 3 + 3 + 4, short side: 7, so every share of the losing
+    // pool rounds down
+    let end_ts = env.ledger().timestamp() + 1000;
+    let call_id = create_registry_call(&env, &registry, &creator, &stake_token, 3, end_ts, 0);
+    stake_on_registry_call(&env, &registry, call_id, &user, 3, true);
+    stake_on_registry_call(&env, &registry, call_id, &other_user, 4, true);
+    stake_on_registry_call(&env, &registry, call_id, &opponent, 7, false);
+    force_settle(&env, &contract_id, call_id, true);
+
+    assert_eq!(client.withdraw_payout(&call_id, &creator), 5u128);
+    assert_eq!(client.withdraw_payout(&call_id, &user), 5u128);
+    assert!(client.try_sweep_dust(&call_id).is_err());
+    assert_eq!(client.withdraw_payout(&call_id, &other_user), 6u128);
+
+    let totals = client.get_payout_totals(&call_id);
+    assert_eq!(totals.paid, 16u128);
+    assert_eq!(totals.claimed_stake, 10u128);
+
+    assert_eq!(client.sweep_dust(&call_id), 1u128);
+    let last_event = env.events().all().last().unwrap();
+    let symbol: Symbol = last_event.1.get(0).unwrap().into_val(&env);
+    assert_eq!(symbol, Symbol::new(&env, "dust_swept"));
+
+    let paid: i128 = [&creator, &user, &other_user, &treasury]
+        .iter()
+        .map(|address| stake_token_client.balance(address))
+        .sum();
+    assert_eq!(paid, 17i128);
+    assert_eq!(client.get_payout_totals(&call_id).paid, 17u128);
+    assert_eq!(client.get_call(&call_id).unwrap().escrow, 0u128);
+    assert_eq!(stake_token_client.balance(&contract_id), 0i128);
+    assert!(client.try_sweep_dust(&call_id).is_err());
+}
+
+#[test]
+fn test_sweep_dust_after_deadline() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    let stake_token = create_stake_token(&env);
+    let stake_token_client = token::Client::new(&env, &stake_token);
+    let (client, registry) = setup_with_registry(&env, &owner);
+    let contract_id = client.address.clone();
+
+    let end_ts = env.ledger().timestamp() + 1000;
+    let call_id = create_registry_call(&env, &registry, &creator, &stake_token, 300, end_ts, 0);
+    stake_on_registry_call(&env, &registry, call_id, &opponent, 200, false);
+    let refund_call = create_registry_call(&env, &registry, &creator, &stake_token, 100, end_ts, 0);
+    force_settle(&env, &contract_id, call_id, true);
+    force_settle(&env, &contract_id, refund_call, true);
+
+    // The winner has not claimed, so the sweep waits for the deadline
+    assert!(client.try_sweep_dust(&call_id).is_err());
+    let deadline = client.get_call(&call_id).unwrap().dispute_deadline + DUST_SWEEP_DELAY;
+    env.ledger().set_timestamp(deadline);
+    assert_eq!(client.sweep_dust(&call_id), 500u128);
+    assert_eq!(stake_token_client.balance(&owner), 500i128);
+
+    // Unopposed calls refund stakes exactly, so there is nothing to sweep
+    assert!(client.try_sweep_dust(&refund_call).is_err());
+}
+
+#[test]
+fn test_withdraw_payout_uses_registry_stakes() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    let outsider = Address::generate(&env);
+    let stake_token = create_stake_token(&env);
+    let stake_token_client = token::Client::new(&env, &stake_token);
+    let (client, registry) = setup_with_registry(&env, &owner);
+    let contract_id = client.address.clone();
+
+    let end_ts = env.ledger().timestamp() + 1000;
+    let call_id = create_registry_call(&env, &registry, &creator, &stake_token, 300, end_ts, 0);
+    stake_on_registry_call(&env, &registry, call_id, &opponent, 200, false);
+
+    force_settle(&env, &contract_id, call_id, false);
+
+    // Neither the losing creator nor an address that never staked receives anything
+    assert_eq!(client.withdraw_payout(&call_id, &creator), 0u128);
+    assert_eq!(client.withdraw_payout(&call_id, &outsider), 0u128);
+    assert_eq!(stake_token_client.balance(&outsider), 0i128);
+
+    // The sole NO staker takes the whole pool
+    assert_eq!(client.withdraw_payout(&call_id, &opponent), 500u128);
+    assert_eq!(stake_token_client.balance(&opponent), 500i128);
+}
+
+#[test]
+#[should_panic]
+fn test_claim_payout_requires_registry_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    let stake_token = create_stake_token(&env);
+    let (client, registry) = setup_with_registry(&env, &owner);
+    let contract_id = client.address.clone();
+
+    let end_ts = env.ledger().timestamp() + 1000;
+    let call_id = create_registry_call(&env, &registry, &creator, &stake_token, 300, end_ts, 0);
+    stake_on_registry_call(&env, &registry, call_id, &opponent, 200, false);
+    force_settle(&env, &contract_id, call_id, false);
+
+    // The opponent cannot inflate their own stake by calling directly
+    env.mock_auths(&[MockAuth {
+        address: &opponent,
+        invoke: &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "claim_payout",
+            args: (call_id, opponent.clone(), 0i128, 500i128).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    client.claim_payout(&call_id, &opponent, &0i128, &500i128);
+}
+
+#[test]
+fn test_claim_many_aggregates_transfers_per_token() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let user = Address::generate(&env);
+    let other = Address::generate(&env);
+    let first_token = create_stake_token(&env);
+    let second_token = create_stake_token(&env);
+    let (client, registry) = setup_with_registry(&env, &owner);
+    let contract_id = client.address.clone();
+    client.set_fee_config(&owner, &100u32, &treasury, &FeeMode::Gross);
+
+    let end_ts = env.ledger().timestamp() + 1000;
+    let won_yes = create_registry_call(&env, &registry, &user, &first_token, 100, end_ts, 0);
+    stake_on_registry_call(&env, &registry, won_yes, &other, 100, false);
+    let won_no = create_registry_call(&env, &registry, &other, &first_token, 300, end_ts, 0);
+    stake_on_registry_call(&env, &registry, won_no, &user, 100, false);
+    let unsettled = create_registry_call(&env, &registry, &user, &first_token, 10, end_ts, 0);
+    stake_on_registry_call(&env, &registry, unsettled, &other, 10, false);
+    let other_token = create_registry_call(&env, &registry, &user, &second_token, 50, end_ts, 0);
+    stake_on_registry_call(&env, &registry, other_token, &other, 150, false);
+
+    force_settle(&env, &contract_id, won_yes, true);
+    force_settle(&env, &contract_id, won_no, false);
+    force_settle(&env, &contract_id, other_token, true);
+    env.ledger().set_timestamp(end_ts + 10);
+
+    let first_token_events = |env: &Env| {
+        env.events()
+            .all()
+            .iter()
+            .filter(|event| event.0 == first_token)
+            .count()
+    };
+    let before = first_token_events(&env);
+
+    let results = client.claim_many(
+        &user,
+        &vec![&env, won_yes, won_no, unsettled, other_token, won_yes],
+    );
+
+    // One transfer to the user per stake token
+    assert_eq!(first_token_events(&env) - before, 1);
+
+    assert_eq!(
+        results,
+        vec![
+            &env,
+            ClaimResult::Paid(198),
+            ClaimResult::Paid(396),
+            ClaimResult::NotSettled,
+            ClaimResult::Paid(198),
+            ClaimResult::AlreadyClaimed,
+        ]
+    );
+    assert_eq!(token::Client::new(&env, &first_token).balance(&user), 594);
+    assert_eq!(token::Client::new(&env, &second_token).balance(&user), 198);
+    assert_eq!(client.accrued_fees(&first_token), 6);
+    assert_eq!(client.accrued_fees(&second_token), 2);
+
+    assert!(client.has_withdrawn(&won_yes, &user));
+    assert!(client.has_withdrawn(&won_no, &user));
+    assert!(!client.has_withdrawn(&unsettled, &user));
+    assert_eq!(client.get_call(&won_no).unwrap().escrow, 0);
+}
+
+#[test]
+fn test_claim_many_skips_provisional_outcomes() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    let (client, registry) = setup_with_registry(&env, &owner);
+
+    let call_id = settle_disputable_call(&env, &client, &registry, &creator, &opponent, true);
+
+    let results = client.claim_many(&creator, &vec![&env, call_id]);
+    assert_eq!(results, vec![&env, ClaimResult::NotSettled]);
+    assert!(!client.has_withdrawn(&call_id, &creator));
+}
+
+#[test]
+fn test_push_payout_pays_user_without_their_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    let keeper = Address::generate(&env);
+    let stake_token = create_stake_token(&env);
+    let stake_token_client = token::Client::new(&env, &stake_token);
+    let (client, registry) = setup_with_registry(&env, &owner);
+    let contract_id = client.address.clone();
+
+    let end_ts = env.ledger().timestamp() + 1000;
+    let call_id = create_registry_call(&env, &registry, &creator, &stake_token, 300, end_ts, 0);
+    stake_on_registry_call(&env, &registry, call_id, &opponent, 200, false);
+    force_settle(&env, &contract_id, call_id, true);
+
+    // No authorization is needed from anyone
+    env.mock_auths(&[]);
+    assert_eq!(client.push_payout(&call_id, &creator), 500u128);
+    assert!(env.auths().is_empty());
+
+    let last_event = env.events().all().last().unwrap();
+    let symbol: Symbol = last_event.1.get(0).unwrap().into_val(&env);
+    assert_eq!(symbol, Symbol::new(&env, "payout_withdrawn"));
+
+    assert_eq!(stake_token_client.balance(&creator), 500i128);
+    assert_eq!(stake_token_client.balance(&keeper), 0i128);
+    assert!(client.has_withdrawn(&call_id, &creator));
+}
+
+#[test]
+#[should_panic(expected = "Already withdrawn")]
+fn test_push_payout_after_withdrawal() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    let stake_token = create_stake_token(&env);
+    let (client, registry) = setup_with_registry(&env, &owner);
+    let contract_id = client.address.clone();
+
+    let end_ts = env.ledger().timestamp() + 1000;
+    let call_id = create_registry_call(&env, &registry, &creator, &stake_token, 300, end_ts, 0);
+    stake_on_registry_call(&env, &registry, call_id, &opponent, 200, false);
+    force_settle(&env, &contract_id, call_id, true);
+
+    client.withdraw_payout(&call_id, &creator);
+    client.push_payout(&call_id, &creator);
+}
+
+#[test]
+#[should_panic(expected = "Outcome not final")]
+fn test_push_payout_during_dispute_period() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    let (client, registry) = setup_with_registry(&env, &owner);
+
+    let call_id = settle_disputable_call(&env, &client, &registry, &creator, &opponent, true);
+    client.push_payout(&call_id, &creator);
+}
+
+#[test]
+#[should_panic(expected = "Already withdrawn")]
+fn test_withdraw_payout_twice() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    let stake_token = create_stake_token(&env);
+    let (client, registry) = setup_with_registry(&env, &owner);
+    let contract_id = client.address.clone();
+
+    let end_ts = env.ledger().timestamp() + 1000;
+    let call_id = create_registry_call(&env, &registry, &creator, &stake_token, 100, end_ts, 0);
+    stake_on_registry_call(&env, &registry, call_id, &opponent, 100, false);
+
+    force_settle(&env, &contract_id, call_id, true);
+
+    client.withdraw_payout(&call_id, &creator);
+    client.withdraw_payout(&call_id, &creator);
+}
+
+#[test]
+fn test_escrow_matches_contract_balance() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let carol = Address::generate(&env);
+    let stake_token = create_stake_token(&env);
+    let stake_token_client = token::Client::new(&env, &stake_token);
+    let (client, registry) = setup_with_registry(&env, &owner);
+    let contract_id = client.address.clone();
+    client.set_fee_config(&owner, &100u32, &treasury, &FeeMode::Gross);
+
+    let end_ts = env.ledger().timestamp() + 1000;
+    let first = create_registry_call(&env, &registry, &alice, &stake_token, 700, end_ts, 0);
+    stake_on_registry_call(&env, &registry, first, &bob, 300, false);
+    stake_on_registry_call(&env, &registry, first, &carol, 333, false);
+    let second = create_registry_call(&env, &registry, &bob, &stake_token, 250, end_ts, 0);
+    stake_on_registry_call(&env, &registry, second, &carol, 125, true);
+    stake_on_registry_call(&env, &registry, second, &alice, 400, false);
+
+    let total_escrow = |client: &OutcomeManagerContractClient| {
+        client.get_call(&first).unwrap().escrow + client.get_call(&second).unwrap().escrow
+    };
+    assert_eq!(total_escrow(&client), 2108u128);
+    assert_eq!(stake_token_client.balance(&contract_id), 2108i128);
+
+    force_settle(&env, &contract_id, first, false);
+    force_settle(&env, &contract_id, second, true);
+
+    for (call_id, user) in [
+        (first, &bob),
+        (first, &carol),
+        (first, &alice),
+        (second, &bob),
+        (second, &carol),
+        (second, &alice),
+    ] {
+        client.withdraw_payout(&call_id, user);
+        assert_eq!(
+            stake_token_client.balance(&contract_id),
+            i128::try_from(total_escrow(&client)).unwrap() + client.accrued_fees(&stake_token)
+        );
+    }
+
+    // Only rounding remainders stay escrowed once every winner has withdrawn
+    assert!(client.get_call(&first).unwrap().escrow < 2u128);
+    assert!(client.get_call(&second).unwrap().escrow < 2u128);
+}
+
+#[test]
+#[should_panic(expected = "Outcome not final")]
+fn test_withdraw_payout_during_dispute_period() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    let (client, registry) = setup_with_registry(&env, &owner);
+
+    let call_id = settle_disputable_call(&env, &client, &registry, &creator, &opponent, true);
+
+    let call_data = client.get_call(&call_id).unwrap();
+    assert!(call_data.settled);
+    assert!(!call_data.finalized);
+    assert_eq!(
+        call_data.dispute_deadline,
+        env.ledger().timestamp() + 86_400
+    );
+
+    client.withdraw_payout(&call_id, &creator);
+}
+
+#[test]
+fn test_unchallenged_outcome_becomes_final() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    let (client, registry) = setup_with_registry(&env, &owner);
+
+    let call_id = settle_disputable_call(&env, &client, &registry, &creator, &opponent, true);
+    let deadline = client.get_call(&call_id).unwrap().dispute_deadline;
+
+    env.ledger().set_timestamp(deadline + 1);
+    client.finalize_outcome(&call_id);
+    assert!(client.get_call(&call_id).unwrap().finalized);
+
+    assert_eq!(client.withdraw_payout(&call_id, &creator), 500u128);
+}
+
+#[test]
+fn test_dispute_bond_required() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    let (client, registry) = setup_with_registry(&env, &owner);
+
+    assert_eq!(client.get_dispute_config_view().bond, DEFAULT_DISPUTE_BOND);
+    assert!(client.try_set_dispute_config(&3_600u64, &0i128).is_err());
+
+    // A free challenge stored before bonds were required is refused
+    env.as_contract(&client.address, || {
+        env.storage().persistent().set(
+            &DISPUTE_CONFIG,
+            &DisputeConfig {
+                period: 3_600,
+                bond: 0,
+            },
+        );
+    });
+    let call_id = settle_disputable_call(&env, &client, &registry, &creator, &opponent, true);
+    assert!(client.try_challenge_outcome(&call_id, &opponent).is_err());
+    assert!(client.get_dispute(&call_id).is_none());
+}
+
+#[test]
+fn test_upheld_challenge_overrides_outcome_and_refunds_bond() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    let (client, registry) = setup_with_registry(&env, &owner);
+    client.set_dispute_config(&3_600u64, &50i128);
+
+    let call_id = settle_disputable_call(&env, &client, &registry, &creator, &opponent, true);
+    let stake_token = client.get_call(&call_id).unwrap().token;
+    let stake_token_client = token::Client::new(&env, &stake_token);
+
+    client.challenge_outcome(&call_id, &opponent);
+    let dispute = client.get_dispute(&call_id).unwrap();
+    assert_eq!(dispute.challenger, opponent);
+    assert_eq!(dispute.bond, 50);
+
+    // Escrow plus the open bond is exactly what the contract holds
+    assert_eq!(stake_token_client.balance(&client.address), 550i128);
+    assert_eq!(client.get_call(&call_id).unwrap().escrow, 500u128);
+
+    // Still blocked after the deadline while the dispute is open
+    env.ledger().set_timestamp(env.ledger().timestamp() + 3_601);
+    assert!(client.try_finalize_outcome(&call_id).is_err());
+
+    client.resolve_dispute(&owner, &call_id, &false, &95u128);
+
+    let call_data = client.get_call(&call_id).unwrap();
+    assert!(call_data.finalized);
+    assert_eq!(call_data.outcome, Some(false));
+    assert_eq!(call_data.final_price, Some(95u128));
+    assert!(client.get_dispute(&call_id).is_none());
+    assert_eq!(stake_token_client.balance(&opponent), 1000i128);
+
+    assert_eq!(client.withdraw_payout(&call_id, &opponent), 500u128);
+    assert_eq!(client.withdraw_payout(&call_id, &creator), 0u128);
+}
+
+#[test]
+fn test_rejected_challenge_slashes_bond() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    let (client, registry) = setup_with_registry(&env, &owner);
+    client.set_fee_config(&owner, &0u32, &treasury, &FeeMode::Gross);
+    client.set_dispute_config(&3_600u64, &50i128);
+
+    let call_id = settle_disputable_call(&env, &client, &registry, &creator, &opponent, true);
+    let stake_token = client.get_call(&call_id).unwrap().token;
+    let stake_token_client = token::Client::new(&env, &stake_token);
+
+    client.challenge_outcome(&call_id, &opponent);
+    client.resolve_dispute(&owner, &call_id, &true, &105u128);
+
+    assert_eq!(stake_token_client.balance(&treasury), 50i128);
+    assert_eq!(stake_token_client.balance(&opponent), 950i128);
+    assert_eq!(stake_token_client.balance(&client.address), 500i128);
+    assert_eq!(client.withdraw_payout(&call_id, &creator), 500u128);
+}
+
+#[test]
+#[should_panic(expected = "Only stakers can challenge")]
+fn test_challenge_requires_stake() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    let outsider = Address::generate(&env);
+    let (client, registry) = setup_with_registry(&env, &owner);
+
+    let call_id = settle_disputable_call(&env, &client, &registry, &creator, &opponent, true);
+    client.challenge_outcome(&call_id, &outsider);
+}
+
+#[test]
+#[should_panic(expected = "Dispute period over")]
+fn test_challenge_after_dispute_period() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    let (client, registry) = setup_with_registry(&env, &owner);
+
+    let call_id = settle_disputable_call(&env, &client, &registry, &creator, &opponent, true);
+    let deadline = client.get_call(&call_id).unwrap().dispute_deadline;
+
+    env.ledger().set_timestamp(deadline + 1);
+    client.challenge_outcome(&call_id, &opponent);
+}
+
+#[test]
+#[should_panic]
+fn test_resolve_dispute_requires_owner_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    let (client, registry) = setup_with_registry(&env, &owner);
+
+    let call_id = settle_disputable_call(&env, &client, &registry, &creator, &opponent, true);
+    client.challenge_outcome(&call_id, &opponent);
+
+    env.mock_auths(&[MockAuth {
+        address: &opponent,
+        invoke: &MockAuthInvoke {
+            contract: &client.address,
+            fn_name: "resolve_dispute",
+            args: (opponent.clone(), call_id, false, 95u128).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    client.resolve_dispute(&opponent, &call_id, &false, &95u128);
+}
+
+#[test]
+fn test_void_call_refunds_every_staker_without_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    let stake_token = create_stake_token(&env);
+    let stake_token_client = token::Client::new(&env, &stake_token);
+    let (client, registry) = setup_with_registry(&env, &owner);
+    client.set_fee_config(&owner, &500u32, &treasury, &FeeMode::Gross);
+
+    let end_ts = env.ledger().timestamp() + 1000;
+    let call_id = create_registry_call(&env, &registry, &creator, &stake_token, 300, end_ts, 0);
+    stake_on_registry_call(&env, &registry, call_id, &opponent, 200, false);
+    stake_on_registry_call(&env, &registry, call_id, &opponent, 50, true);
+
+    client.void_call(&owner, &call_id);
+
+    let call_data = client.get_call(&call_id).unwrap();
+    assert!(call_data.settled);
+    assert!(call_data.voided);
+    assert!(call_data.finalized);
+    assert_eq!(call_data.outcome, None);
+
+    assert_eq!(client.withdraw_payout(&call_id, &creator), 300u128);
+    assert_eq!(client.withdraw_payout(&call_id, &opponent), 250u128);
+    assert_eq!(stake_token_client.balance(&creator), 300i128);
+    assert_eq!(stake_token_client.balance(&opponent), 250i128);
+    assert_eq!(stake_token_client.balance(&treasury), 0i128);
+    assert_eq!(stake_token_client.balance(&client.address), 0i128);
+    assert_eq!(client.get_call(&call_id).unwrap().escrow, 0u128);
+}
+
+#[test]
+fn test_submit_void_by_oracle() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, OutcomeManagerContract);
+    let client = OutcomeManagerContractClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let registry = Address::generate(&env);
+    let token = Address::generate(&env);
+    let (signing_key, oracle) = oracle_key(&env, 4);
+
+    client.initialize(&owner, &registry);
+    client.set_oracle(&owner, &oracle, &true);
+    client.register_call(&1u64, &token, &1000u128, &500u128, &1000000u64);
+
+    env.ledger().set_timestamp(1000020);
+    let message = client.void_message(&1u64, &1000010u64);
+    assert_ne!(
+        message,
+        client.outcome_message(&1u64, &false, &0u128, &1000010u64)
+    );
+
+    let signatures = vec![&env, (oracle, sign_bytes(&env, &signing_key, &message))];
+    assert!(client.submit_void(&1u64, &1000010u64, &signatures));
+
+    let call_data = client.get_call(&1u64).unwrap();
+    assert!(call_data.voided);
+    assert!(call_data.finalized);
+    assert_eq!(call_data.outcome, None);
+    assert_eq!(call_data.final_price, None);
+}
+
+#[test]
+fn test_void_call_closes_open_dispute() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    let (client, registry) = setup_with_registry(&env, &owner);
+    client.set_dispute_config(&3_600u64, &50i128);
+
+    let call_id = settle_disputable_call(&env, &client, &registry, &creator, &opponent, true);
+    let stake_token = client.get_call(&call_id).unwrap().token;
+    let stake_token_client = token::Client::new(&env, &stake_token);
+
+    client.challenge_outcome(&call_id, &opponent);
+    client.void_call(&owner, &call_id);
+
+    assert!(client.get_dispute(&call_id).is_none());
+    assert_eq!(stake_token_client.balance(&opponent), 1000i128);
+    assert_eq!(client.withdraw_payout(&call_id, &opponent), 200u128);
+    assert_eq!(client.withdraw_payout(&call_id, &creator), 300u128);
+}
+
+#[test]
+#[should_panic(expected = "Outcome already final")]
+fn test_void_call_after_final_outcome() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    let (client, registry) = setup_with_registry(&env, &owner);
+
+    let call_id = settle_disputable_call(&env, &client, &registry, &creator, &opponent, true);
+    let deadline = client.get_call(&call_id).unwrap().dispute_deadline;
+
+    env.ledger().set_timestamp(deadline + 1);
+    client.void_call(&owner, &call_id);
+}
+
+#[test]
+#[should_panic(expected = "Call already settled")]
+fn test_stake_on_voided_call() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let staker = Address::generate(&env);
+    let stake_token = create_stake_token(&env);
+    let (client, registry) = setup_with_registry(&env, &owner);
+
+    let end_ts = env.ledger().timestamp() + 1000;
+    let call_id = create_registry_call(&env, &registry, &creator, &stake_token, 300, end_ts, 0);
+
+    client.void_call(&owner, &call_id);
+    stake_on_registry_call(&env, &registry, call_id, &staker, 100, false);
+}
+
+#[test]
+#[should_panic]
+fn test_void_call_requires_owner_auth() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, OutcomeManagerContract);
+    let client = OutcomeManagerContractClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let registry = Address::generate(&env);
+    let attacker = Address::generate(&env);
+    let token = Address::generate(&env);
+
+    client.initialize(&owner, &registry);
+
+    env.mock_auths(&[MockAuth {
+        address: &registry,
+        invoke: &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "register_call",
+            args: (1u64, token.clone(), 1000u128, 500u128, 1000000u64).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    client.register_call(&1u64, &token, &1000u128, &500u128, &1000000u64);
+
+    env.mock_auths(&[MockAuth {
+        address: &attacker,
+        invoke: &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "void_call",
+            args: (attacker.clone(), 1u64).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    client.void_call(&attacker, &1u64);
+}
+
+#[test]
+fn test_unopposed_call_refunds_without_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let backer = Address::generate(&env);
+    let stake_token = create_stake_token(&env);
+    let stake_token_client = token::Client::new(&env, &stake_token);
+    let (client, registry) = setup_with_registry(&env, &owner);
+    client.set_fee_config(&owner, &500u32, &treasury, &FeeMode::Gross);
+
+    let end_ts = env.ledger().timestamp() + 1000;
+    let call_id = create_registry_call(&env, &registry, &creator, &stake_token, 300, end_ts, 0);
+    stake_on_registry_call(&env, &registry, call_id, &backer, 200, true);
+    assert_eq!(client.call_status(&call_id), CallStatus::Open);
+
+    // No outcome is needed once the call has ended
+    env.ledger().set_timestamp(end_ts);
+    assert_eq!(client.call_status(&call_id), CallStatus::Unopposed);
+
+    assert_eq!(client.withdraw_payout(&call_id, &creator), 300u128);
+    assert_eq!(client.withdraw_payout(&call_id, &backer), 200u128);
+    assert_eq!(stake_token_client.balance(&creator), 300i128);
+    assert_eq!(stake_token_client.balance(&backer), 200i128);
+    assert_eq!(stake_token_client.balance(&treasury), 0i128);
+    assert_eq!(client.get_call(&call_id).unwrap().escrow, 0u128);
+}
+
+#[test]
+#[should_panic(expected = "Call not settled")]
+fn test_unopposed_call_refund_before_end() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let stake_token = create_stake_token(&env);
+    let (client, registry) = setup_with_registry(&env, &owner);
+
+    let end_ts = env.ledger().timestamp() + 1000;
+    let call_id = create_registry_call(&env, &registry, &creator, &stake_token, 300, end_ts, 0);
+
+    client.withdraw_payout(&call_id, &creator);
+}
+
+#[test]
+fn test_unopposed_call_settled_for_empty_side() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let stake_token = create_stake_token(&env);
+    let (client, registry) = setup_with_registry(&env, &owner);
+    client.set_fee_config(&owner, &500u32, &treasury, &FeeMode::Gross);
+
+    let end_ts = env.ledger().timestamp() + 1000;
+    let call_id = create_registry_call(&env, &registry, &creator, &stake_token, 300, end_ts, 0);
+
+    // NO wins with nobody staked on it; the creator is refunded instead of
+    // dividing by an empty winning side
+    env.ledger().set_timestamp(end_ts + 10);
+    force_settle(&env, &client.address, call_id, false);
+
+    assert_eq!(client.call_status(&call_id), CallStatus::Unopposed);
+    assert_eq!(client.withdraw_payout(&call_id, &creator), 300u128);
+    assert_eq!(
+        token::Client::new(&env, &stake_token).balance(&treasury),
+        0i128
+    );
+}
+
+#[test]
+fn test_call_status_lifecycle() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    let (client, registry) = setup_with_registry(&env, &owner);
+    client.set_dispute_config(&100u64, &50i128);
+
+    let call_id = settle_disputable_call(&env, &client, &registry, &creator, &opponent, true);
+    assert_eq!(client.call_status(&call_id), CallStatus::Provisional);
+
+    client.challenge_outcome(&call_id, &opponent);
+    assert_eq!(client.call_status(&call_id), CallStatus::Disputed);
+
+    client.resolve_dispute(&owner, &call_id, &true, &105u128);
+    assert_eq!(client.call_status(&call_id), CallStatus::Final);
+
+    let stake_token = create_stake_token(&env);
+    let end_ts = env.ledger().timestamp() + 1000;
+    let open_id = create_registry_call(&env, &registry, &creator, &stake_token, 300, end_ts, 0);
+    stake_on_registry_call(&env, &registry, open_id, &opponent, 200, false);
+    assert_eq!(client.call_status(&open_id), CallStatus::Open);
+
+    env.ledger().set_timestamp(end_ts);
+    assert_eq!(client.call_status(&open_id), CallStatus::AwaitingOutcome);
+
+    client.void_call(&owner, &open_id);
+    assert_eq!(client.call_status(&open_id), CallStatus::Voided);
+}
+
 #[test]
-fn test_initialize() {
+fn test_cancelled_call_cannot_be_settled() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let stake_token = create_stake_token(&env);
+    let (client, registry) = setup_with_registry(&env, &owner);
+    let (signing_key, oracle) = oracle_key(&env, 4);
+    client.set_oracle(&owner, &oracle, &true);
+
+    let end_ts = env.ledger().timestamp() + 1000;
+    let call_id = create_registry_call(&env, &registry, &creator, &stake_token, 300, end_ts, 0);
+    registry.cancel_call(&call_id);
+    assert_eq!(client.call_status(&call_id), CallStatus::Cancelled);
+
+    env.ledger().set_timestamp(end_ts + 10);
+    let signature = sign_outcome(&env, &client, &signing_key, call_id, true, 105, end_ts);
+    let result = client.try_submit_outcome(&call_id, &true, &105u128, &end_ts, &oracle, &signature);
+    assert!(result.is_err());
+    assert!(client.try_withdraw_payout(&call_id, &creator).is_err());
+}
+
+#[test]
+fn test_thousands_of_calls_use_independent_storage() {
     let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    let stake_token = create_stake_token(&env);
+    let (client, registry) = setup_with_registry(&env, &owner);
+
+    // Seed thousands of settled calls, some already withdrawn by a user
+    let end_ts = env.ledger().timestamp() + 1000;
+    env.budget().reset_unlimited();
+    env.as_contract(&client.address, || {
+        for call_id in 1_000_000..1_002_000u64 {
+            let key = DataKey::Call(call_id);
+            let call_data = CallData {
+                id: call_id,
+                token: stake_token.clone(),
+                long_tokens: 100,
+                short_tokens: 50,
+                end_ts,
+                settled: true,
+                outcome: Some(true),
+                final_price: Some(105),
+                escrow: 150,
+                dispute_deadline: end_ts,
+                finalized: true,
+                voided: false,
+                cancelled: false,
+            };
+            env.storage().persistent().set(&key, &call_data);
+            if call_id % 10 == 0 {
+                env.storage()
+                    .persistent()
+                    .set(&DataKey::Withdrawal(call_id, opponent.clone()), &true);
+            }
+        }
+    });
+
+    env.budget().reset_default();
+    client.register_call(&1_002_000, &stake_token, &100u128, &50u128, &end_ts);
+
+    let call_id = create_registry_call(&env, &registry, &creator, &stake_token, 300, end_ts, 0);
+    stake_on_registry_call(&env, &registry, call_id, &opponent, 200, false);
+    env.ledger().set_timestamp(end_ts + 10);
+    force_settle(&env, &client.address, call_id, true);
+
+    // A withdrawal only touches its own call's entries, so it fits the
+    // default budget however many calls exist
+    env.budget().reset_default();
+    assert_eq!(client.withdraw_payout(&call_id, &creator), 500u128);
+    assert!(client.has_withdrawn(&call_id, &creator));
+    assert!(!client.has_withdrawn(&1_000_000, &creator));
+    assert!(client.has_withdrawn(&1_001_990, &opponent));
+    assert!(!client.has_withdrawn(&1_001_999, &opponent));
+    assert_eq!(client.get_call(&1_002_000).unwrap().escrow, 150u128);
+}
+
+/// Registers the contract with the layout a schema version 1 deployment
+/// left behind: no stored version and legacy `CallDataV1` maps
+fn setup_v1_storage<'a>(
+    env: &Env,
+    owner: &Address,
+    user: &Address,
+    call_count: u64,
+) -> OutcomeManagerContractClient<'a> {
     let contract_id = env.register_contract(None, OutcomeManagerContract);
-    let client = OutcomeManagerContractClient::new(&env, &contract_id);
+    let client = OutcomeManagerContractClient::new(env, &contract_id);
+    client.initialize(owner, &Address::generate(env));
+    let token = Address::generate(env);
+
+    env.as_contract(&contract_id, || {
+        let mut calls: Map<u64, CallDataV1> = Map::new(env);
+        let mut withdrawals: Map<(u64, Address), bool> = Map::new(env);
+        for call_id in 0..call_count {
+            let settled = call_id % 2 == 0;
+            calls.set(
+                call_id,
+                CallDataV1 {
+                    id: call_id,
+                    token: token.clone(),
+                    long_tokens: 100,
+                    short_tokens: 50,
+                    end_ts: 1000,
+                    settled,
+                    outcome: if settled { Some(true) } else { None },
+                    final_price: if settled { Some(105) } else { None },
+                },
+            );
+            if settled {
+                withdrawals.set((call_id, user.clone()), true);
+            }
+        }
+
+        let instance = env.storage().instance();
+        instance.set(&CALLS, &calls);
+        instance.set(&WITHDRAWALS, &withdrawals);
+        instance.remove(&SCHEMA_VERSION);
+        env.storage().persistent().set(
+            &FEE_CONFIG,
+            &FeeConfigV1 {
+                basis_points: 250,
+                treasury: owner.clone(),
+            },
+        );
+    });
 
+    client
+}
+
+#[test]
+fn test_migrate_moves_v1_storage_in_batches() {
+    let env = Env::default();
+    env.mock_all_auths();
     let owner = Address::generate(&env);
-    let registry = Address::generate(&env);
+    let user = Address::generate(&env);
 
-    client.initialize(&owner, &registry);
+    // Moving thousands of legacy entries exceeds the default per-transaction
+    // budget, which is why the migration is batched
+    env.budget().reset_unlimited();
+    let client = setup_v1_storage(&env, &owner, &user, 1_000);
+    assert_eq!(client.get_schema_version(), 1);
 
-    // Verify oracle returns false for non-existent oracle
-    let random_oracle = BytesN::from_array(&env, &[1; 32]);
-    assert!(!client.is_authorized_oracle(&random_oracle));
-    assert!(!client.get_is_paused());
+    // 1,000 calls and 500 withdrawal flags
+    assert_eq!(client.migrate(&600u32), 1);
+    assert_eq!(client.migrate(&600u32), 1);
+    assert_eq!(client.migrate(&600u32), CURRENT_SCHEMA_VERSION);
+    assert_eq!(client.get_schema_version(), CURRENT_SCHEMA_VERSION);
+
+    let settled = client.get_call(&998).unwrap();
+    assert!(settled.settled);
+    assert!(settled.finalized);
+    assert_eq!(settled.outcome, Some(true));
+    assert_eq!(settled.escrow, 0);
+    let open = client.get_call(&999).unwrap();
+    assert!(!open.settled);
+    assert!(!open.finalized);
+    assert!(client.has_withdrawn(&998, &user));
+    assert!(!client.has_withdrawn(&999, &user));
 
     let fee_config = client.get_fee_config_view();
-    assert_eq!(fee_config.basis_points, 0);
+    assert_eq!(fee_config.basis_points, 250);
     assert_eq!(fee_config.treasury, owner);
+    assert_eq!(fee_config.mode, FeeMode::Gross);
+    env.as_contract(&client.address, || {
+        let instance = env.storage().instance();
+        assert!(!instance.has(&CALLS));
+        assert!(!instance.has(&WITHDRAWALS));
+        assert!(env.storage().persistent().has(&DataKey::Call(0)));
+    });
+
+    // Nothing is left to migrate
+    assert!(client.try_migrate(&600u32).is_err());
 }
 
 #[test]
-fn test_set_oracle() {
+fn test_migrated_settled_calls_not_swept_early() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let user = Address::generate(&env);
+    let client = setup_v1_storage(&env, &owner, &user, 2);
+
+    // Long after the version 1 outcome was settled
+    let migrated_at = 2 * DUST_SWEEP_DELAY;
+    env.ledger().set_timestamp(migrated_at);
+    assert_eq!(client.migrate(&10u32), CURRENT_SCHEMA_VERSION);
+    assert_eq!(client.get_call(&0).unwrap().dispute_deadline, migrated_at);
+
+    // The registry forwards the stakes, and winners still have the full
+    // delay to claim them
+    let token = client.get_call(&0).unwrap().token;
+    client.fund_migrated_call(&0, &token, &100u128, &50u128, &1000u64);
+    assert_eq!(client.get_call(&0).unwrap().escrow, 150);
+    assert!(client.try_sweep_dust(&0).is_err());
+}
+
+#[test]
+#[should_panic(expected = "Schema already current")]
+fn test_migrate_current_schema() {
     let env = Env::default();
+    env.mock_all_auths();
     let contract_id = env.register_contract(None, OutcomeManagerContract);
     let client = OutcomeManagerContractClient::new(&env, &contract_id);
 
+    client.initialize(&Address::generate(&env), &Address::generate(&env));
+    assert_eq!(client.get_schema_version(), CURRENT_SCHEMA_VERSION);
+    client.migrate(&100u32);
+}
+
+#[test]
+#[should_panic]
+fn test_migrate_requires_owner_auth() {
+    let env = Env::default();
     let owner = Address::generate(&env);
-    let registry = Address::generate(&env);
-    let oracle = BytesN::from_array(&env, &[2; 32]);
+    let attacker = Address::generate(&env);
+    env.mock_all_auths();
+    let client = setup_v1_storage(&env, &owner, &attacker, 10);
+
+    env.mock_auths(&[MockAuth {
+        address: &attacker,
+        invoke: &MockAuthInvoke {
+            contract: &client.address,
+            fn_name: "migrate",
+            args: (100u32,).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    client.migrate(&100u32);
+}
 
+#[test]
+fn test_upgrade() {
+    let env = Env::default();
     env.mock_all_auths();
-    client.initialize(&owner, &registry);
+    let contract_id = env.register_contract(None, OutcomeManagerContract);
+    let client = OutcomeManagerContractClient::new(&env, &contract_id);
+    client.initialize(&Address::generate(&env), &Address::generate(&env));
 
-    // Set oracle as authorized
-    client.set_oracle(&oracle, &true);
+    let wasm_hash = env.deployer().upload_contract_wasm(minimal_wasm(&env));
+    client.upgrade(&wasm_hash);
 
-    // Verify oracle is authorized
-    assert!(client.is_authorized_oracle(&oracle));
+    let last_event = env.events().all().last().unwrap();
+    assert_eq!(last_event.0, contract_id);
+    let topic: Symbol = last_event.1.get(0).unwrap().into_val(&env);
+    assert_eq!(topic, Symbol::new(&env, "contract_upgraded"));
+}
 
-    // Revoke oracle
-    client.set_oracle(&oracle, &false);
-    assert!(!client.is_authorized_oracle(&oracle));
+#[test]
+#[should_panic]
+fn test_upgrade_requires_owner_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, OutcomeManagerContract);
+    let client = OutcomeManagerContractClient::new(&env, &contract_id);
+    let attacker = Address::generate(&env);
+    client.initialize(&Address::generate(&env), &Address::generate(&env));
+    let wasm_hash = env.deployer().upload_contract_wasm(minimal_wasm(&env));
+
+    env.mock_auths(&[MockAuth {
+        address: &attacker,
+        invoke: &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "upgrade",
+            args: (wasm_hash.clone(),).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    client.upgrade(&wasm_hash);
 }
 
 #[test]
-fn test_register_call() {
+fn test_bump_call_extends_ttl() {
     let env = Env::default();
+    env.mock_all_auths();
     let contract_id = env.register_contract(None, OutcomeManagerContract);
     let client = OutcomeManagerContractClient::new(&env, &contract_id);
 
     let owner = Address::generate(&env);
     let registry = Address::generate(&env);
     let token = Address::generate(&env);
-
     client.initialize(&owner, &registry);
+    client.set_ttl_config(&5_000u32, &10_000u32);
+    client.register_call(&1u64, &token, &1000u128, &500u128, &1000000u64);
 
-    let call_id = 1u64;
-    let long_tokens = 1000u128;
-    let short_tokens = 500u128;
-    let end_ts = 1000000u64;
+    let key = DataKey::Call(1);
+    env.as_contract(&contract_id, || {
+        assert_eq!(env.storage().persistent().get_ttl(&key), 10_000);
+        assert_eq!(env.storage().instance().get_ttl(), 10_000);
+    });
 
-    client.register_call(&call_id, &token, &long_tokens, &short_tokens, &end_ts);
+    // Above the threshold nothing changes; below it the call is extended
+    env.ledger().with_mut(|li| li.sequence_number += 4_000);
+    client.bump_call(&1u64);
+    env.as_contract(&contract_id, || {
+        assert_eq!(env.storage().persistent().get_ttl(&key), 6_000);
+    });
 
-    // Verify call was registered
-    let call = client.get_call(&call_id);
-    assert!(call.is_some());
+    env.ledger().with_mut(|li| li.sequence_number += 2_000);
+    client.bump_call(&1u64);
+    env.as_contract(&contract_id, || {
+        assert_eq!(env.storage().persistent().get_ttl(&key), 10_000);
+        assert_eq!(env.storage().persistent().get_ttl(&FEE_CONFIG), 10_000);
+        assert_eq!(env.storage().instance().get_ttl(), 10_000);
+    });
+}
 
-    let call_data = call.unwrap();
-    assert_eq!(call_data.id, call_id);
-    assert_eq!(call_data.long_tokens, long_tokens);
-    assert_eq!(call_data.short_tokens, short_tokens);
-    assert!(!call_data.settled);
+#[test]
+#[should_panic(expected = "extend_to exceeds max TTL")]
+fn test_set_ttl_config_above_max_ttl() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, OutcomeManagerContract);
+    let client = OutcomeManagerContractClient::new(&env, &contract_id);
+
+    client.initialize(&Address::generate(&env), &Address::generate(&env));
+    let max_ttl = env.as_contract(&contract_id, || env.storage().max_ttl());
+    client.set_ttl_config(&5_000u32, &(max_ttl + 1));
 }
 
 #[test]
-fn test_submit_outcome_success() {
+fn test_two_step_owner_transfer() {
     let env = Env::default();
+    env.mock_all_auths();
     let contract_id = env.register_contract(None, OutcomeManagerContract);
     let client = OutcomeManagerContractClient::new(&env, &contract_id);
 
     let owner = Address::generate(&env);
-    let registry = Address::generate(&env);
-    let token = Address::generate(&env);
-    let oracle = BytesN::from_array(&env, &[4; 32]);
+    let new_owner = Address::generate(&env);
+    client.initialize(&owner, &Address::generate(&env));
 
+    client.propose_owner(&new_owner);
+    assert_eq!(client.get_pending_owner(), Some(new_owner.clone()));
+    assert_eq!(client.get_owner(), owner);
+
+    client.accept_owner();
+    assert_eq!(env.auths()[0].0, new_owner);
+    assert_eq!(client.get_owner(), new_owner);
+    assert_eq!(client.get_pending_owner(), None);
+
+    client.pause(&new_owner);
+    assert_eq!(env.auths()[0].0, new_owner);
+    assert!(client.try_unpause(&owner).is_err());
+}
+
+#[test]
+#[should_panic(expected = "No pending owner")]
+fn test_cancel_owner_proposal() {
+    let env = Env::default();
     env.mock_all_auths();
-    client.initialize(&owner, &registry);
+    let contract_id = env.register_contract(None, OutcomeManagerContract);
+    let client = OutcomeManagerContractClient::new(&env, &contract_id);
+    client.initialize(&Address::generate(&env), &Address::generate(&env));
 
-    // Authorize oracle
-    client.set_oracle(&oracle, &true);
+    client.propose_owner(&Address::generate(&env));
+    client.cancel_owner_proposal();
+    assert_eq!(client.get_pending_owner(), None);
 
-    // Register a call
-    let call_id = 1u64;
-    client.register_call(&call_id, &token, &1000u128, &500u128, &1000000u64);
+    client.accept_owner();
+}
+
+#[test]
+#[should_panic]
+fn test_accept_owner_requires_proposed_owner_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, OutcomeManagerContract);
+    let client = OutcomeManagerContractClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    client.initialize(&owner, &Address::generate(&env));
+    client.propose_owner(&Address::generate(&env));
 
-    // Note: In real scenarios, we'd sign the message.
-    // This test ensures the contract can be called with valid types.
+    env.mock_auths(&[MockAuth {
+        address: &owner,
+        invoke: &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "accept_owner",
+            args: ().into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    client.accept_owner();
 }
 
 #[test]
-fn test_withdraw_payout_long_wins() {
+fn test_grant_and_revoke_roles() {
     let env = Env::default();
     env.mock_all_auths();
     let contract_id = env.register_contract(None, OutcomeManagerContract);
     let client = OutcomeManagerContractClient::new(&env, &contract_id);
 
     let owner = Address::generate(&env);
-    let registry = Address::generate(&env);
+    let pauser = Address::generate(&env);
+    let fee_manager = Address::generate(&env);
+    let oracle_manager = Address::generate(&env);
     let treasury = Address::generate(&env);
-    let user = Address::generate(&env);
-    let stake_token_admin = Address::generate(&env);
-    let stake_token_contract = env.register_stellar_asset_contract_v2(stake_token_admin.clone());
-    let stake_token = stake_token_contract.address();
-    let stake_token_client = token::Client::new(&env, &stake_token);
-    let stake_token_admin_client = token::StellarAssetClient::new(&env, &stake_token);
+    let (_, oracle) = oracle_key(&env, 4);
+    client.initialize(&owner, &Address::generate(&env));
 
-    client.initialize(&owner, &registry);
-    client.set_fee_config(&500u32, &treasury);
+    assert!(client.has_role(&Role::Pauser, &owner));
+    assert!(!client.has_role(&Role::Pauser, &pauser));
 
-    // Register a call
-    let call_id = 1u64;
-    client.register_call(&call_id, &stake_token, &1000u128, &500u128, &1000000u64);
+    client.grant_role(&Role::Pauser, &pauser);
+    client.grant_role(&Role::FeeManager, &fee_manager);
+    client.grant_role(&Role::OracleManager, &oracle_manager);
+    assert!(client.has_role(&Role::Pauser, &pauser));
+    assert!(!client.has_role(&Role::FeeManager, &pauser));
 
-    env.as_contract(&contract_id, || {
-        let mut calls: soroban_sdk::Map<u64, CallData> =
-            env.storage().instance().get(&CALLS).unwrap();
-        let mut call_data = calls.get(call_id).unwrap();
-        call_data.settled = true;
-        call_data.outcome = Some(true);
-        call_data.final_price = Some(105u128);
-        calls.set(call_id, call_data);
-        env.storage().instance().set(&CALLS, &calls);
-    });
+    client.pause(&pauser);
+    assert!(client.get_is_paused());
+    client.unpause(&pauser);
+    client.set_fee_config(&fee_manager, &250u32, &treasury, &FeeMode::Gross);
+    assert_eq!(client.get_fee_config_view().basis_points, 250);
+    client.set_oracle(&oracle_manager, &oracle, &true);
+    client.set_oracle_threshold(&oracle_manager, &1u32);
+    assert!(client.is_authorized_oracle(&oracle));
 
-    stake_token_admin_client.mint(&contract_id, &1500);
+    // Each role only unlocks its own entrypoints
+    assert!(client
+        .try_set_fee_config(&pauser, &0u32, &treasury, &FeeMode::Gross)
+        .is_err());
+    assert!(client.try_pause(&fee_manager).is_err());
+    assert!(client
+        .try_set_oracle(&fee_manager, &oracle, &false)
+        .is_err());
 
-    let payout = client.withdraw_payout(&call_id, &user, &100u128, &true);
+    client.revoke_role(&Role::Pauser, &pauser);
+    assert!(!client.has_role(&Role::Pauser, &pauser));
+    assert!(client.try_pause(&pauser).is_err());
 
-    assert_eq!(payout, 143u128);
-    assert_eq!(stake_token_client.balance(&user), 143i128);
-    assert_eq!(stake_token_client.balance(&treasury), 7i128);
-    assert_eq!(stake_token_client.balance(&contract_id), 1350i128);
+    let last_event = env.events().all().last().unwrap();
+    let topic: Symbol = last_event.1.get(0).unwrap().into_val(&env);
+    assert_eq!(topic, Symbol::new(&env, "role_revoked"));
+}
+
+#[test]
+fn test_settlement_arbiter_resolves_disputes() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let arbiter = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    let (client, registry) = setup_with_registry(&env, &owner);
+    client.grant_role(&Role::SettlementArbiter, &arbiter);
+    client.set_dispute_config(&3_600u64, &50i128);
+
+    let call_id = settle_disputable_call(&env, &client, &registry, &creator, &opponent, true);
+    client.challenge_outcome(&call_id, &opponent);
+    assert!(client
+        .try_resolve_dispute(&creator, &call_id, &true, &105u128)
+        .is_err());
+
+    client.resolve_dispute(&arbiter, &call_id, &true, &105u128);
+    assert_eq!(env.auths()[0].0, arbiter);
+    assert_eq!(client.call_status(&call_id), CallStatus::Final);
+}
+
+#[test]
+#[should_panic]
+fn test_grant_role_requires_owner_auth() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, OutcomeManagerContract);
+    let client = OutcomeManagerContractClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let attacker = Address::generate(&env);
+    client.initialize(&owner, &Address::generate(&env));
+
+    env.mock_auths(&[MockAuth {
+        address: &attacker,
+        invoke: &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "grant_role",
+            args: (Role::Pauser, attacker.clone()).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    client.grant_role(&Role::Pauser, &attacker);
 }
 
 #[test]
@@ -182,7 +2278,7 @@ fn test_submit_outcome_when_paused() {
     let registry = Address::generate(&env);
 
     client.initialize(&owner, &registry);
-    client.pause();
+    client.pause(&owner);
 
     client.submit_outcome(
         &1u64,
@@ -212,11 +2308,11 @@ fn test_pause_requires_owner_auth() {
         invoke: &MockAuthInvoke {
             contract: &contract_id,
             fn_name: "pause",
-            args: ().into_val(&env),
+            args: (attacker.clone(),).into_val(&env),
             sub_invokes: &[],
         },
     }]);
-    client.pause();
+    client.pause(&attacker);
 }
 
 #[test]
@@ -237,22 +2333,22 @@ fn test_unpause_requires_owner_auth() {
         invoke: &MockAuthInvoke {
             contract: &contract_id,
             fn_name: "pause",
-            args: ().into_val(&env),
+            args: (owner.clone(),).into_val(&env),
             sub_invokes: &[],
         },
     }]);
-    client.pause();
+    client.pause(&owner);
 
     env.mock_auths(&[MockAuth {
         address: &attacker,
         invoke: &MockAuthInvoke {
             contract: &contract_id,
             fn_name: "unpause",
-            args: ().into_val(&env),
+            args: (attacker.clone(),).into_val(&env),
             sub_invokes: &[],
         },
     }]);
-    client.unpause();
+    client.unpause(&attacker);
 }
 
 #[test]
@@ -268,11 +2364,15 @@ fn test_set_fee_config() {
     let treasury = Address::generate(&env);
 
     client.initialize(&owner, &registry);
-    client.set_fee_config(&250u32, &treasury);
+    client.set_fee_config(&owner, &250u32, &treasury, &FeeMode::Gross);
 
     let fee_config = client.get_fee_config_view();
     assert_eq!(fee_config.basis_points, 250);
     assert_eq!(fee_config.treasury, treasury);
+    assert_eq!(fee_config.mode, FeeMode::Gross);
+
+    client.set_fee_config(&owner, &250u32, &treasury, &FeeMode::Profit);
+    assert_eq!(client.get_fee_config_view().mode, FeeMode::Profit);
 }
 
 #[test]
@@ -294,9 +2394,97 @@ fn test_set_fee_config_requires_owner_auth() {
         invoke: &MockAuthInvoke {
             contract: &contract_id,
             fn_name: "set_fee_config",
-            args: (250u32, treasury.clone()).into_val(&env),
+            args: (attacker.clone(), 250u32, treasury.clone(), FeeMode::Gross).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    client.set_fee_config(&attacker, &250u32, &treasury, &FeeMode::Gross);
+}
+
+#[test]
+fn test_fee_snapshotted_at_registration() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let new_treasury = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    let stake_token = create_stake_token(&env);
+    let (client, registry) = setup_with_registry(&env, &owner);
+    let contract_id = client.address.clone();
+    client.set_fee_config(&owner, &100u32, &treasury, &FeeMode::Gross);
+
+    let end_ts = env.ledger().timestamp() + 1000;
+    let call_id = create_registry_call(&env, &registry, &creator, &stake_token, 300, end_ts, 0);
+    stake_on_registry_call(&env, &registry, call_id, &opponent, 200, false);
+
+    // Raising the fee after the call opened does not affect its payouts
+    client.set_fee_config(&owner, &1_000u32, &new_treasury, &FeeMode::Gross);
+    let later = create_registry_call(&env, &registry, &creator, &stake_token, 100, end_ts, 0);
+    assert_eq!(client.get_call_fee(&call_id).basis_points, 100);
+    assert_eq!(client.get_call_fee(&call_id).mode, FeeMode::Gross);
+    assert_eq!(client.get_call_fee(&later).basis_points, 1_000);
+
+    force_settle(&env, &contract_id, call_id, true);
+    assert_eq!(client.withdraw_payout(&call_id, &creator), 495u128);
+    assert_eq!(client.accrued_fees(&stake_token), 5i128);
+}
+
+#[test]
+#[should_panic(expected = "Fee exceeds max fee")]
+fn test_set_fee_config_above_max_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, OutcomeManagerContract);
+    let client = OutcomeManagerContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    client.initialize(&owner, &Address::generate(&env));
+
+    assert_eq!(client.get_max_fee_view(), 1_000);
+    client.set_fee_config(&owner, &1_001u32, &owner, &FeeMode::Gross);
+}
+
+#[test]
+fn test_set_max_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, OutcomeManagerContract);
+    let client = OutcomeManagerContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    client.initialize(&owner, &Address::generate(&env));
+
+    client.set_max_fee(&2_000u32);
+    assert_eq!(client.get_max_fee_view(), 2_000);
+    client.set_fee_config(&owner, &2_000u32, &owner, &FeeMode::Gross);
+
+    // The ceiling cannot drop below the fee in force or exceed 100%
+    assert!(client.try_set_max_fee(&1_000u32).is_err());
+    assert!(client.try_set_max_fee(&10_001u32).is_err());
+    assert_eq!(client.get_max_fee_view(), 2_000);
+}
+
+#[test]
+#[should_panic]
+fn test_set_max_fee_requires_owner_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, OutcomeManagerContract);
+    let client = OutcomeManagerContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let fee_manager = Address::generate(&env);
+    client.initialize(&owner, &Address::generate(&env));
+    client.grant_role(&Role::FeeManager, &fee_manager);
+
+    // A fee manager cannot lift the ceiling it is bound by
+    env.mock_auths(&[MockAuth {
+        address: &fee_manager,
+        invoke: &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "set_max_fee",
+            args: (5_000u32,).into_val(&env),
             sub_invokes: &[],
         },
     }]);
-    client.set_fee_config(&250u32, &treasury);
+    client.set_max_fee(&5_000u32);
 }